@@ -1,7 +1,12 @@
-
 fn main() {
-    if cfg!(target_os = "windows") {
+    println!("cargo:rerun-if-changed=assets/icon.ico");
+
+    // Embed the executable icon on Windows. Other platforms get their window
+    // icon at runtime from the same file, see setup::set_window_icon.
+    #[cfg(windows)]
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
         let mut res = winres::WindowsResource::new();
         res.set_icon("assets/icon.ico");
         res.compile().unwrap();
-}}
\ No newline at end of file
+    }
+}
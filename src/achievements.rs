@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use crate::components::{AchievementToast, AchievementsText, AchievementsUI};
+use crate::game::GameSet;
+use crate::garage::GarageMenu;
+use crate::persistence;
+use crate::resources::{Achievement, Achievements, GameOver, InMenu, Settings, ACHIEVEMENTS_FILE};
+use crate::stats::StatsMenu;
+use crate::systems::AchievementUnlocked;
+use crate::ui::UiClick;
+
+const TOAST_SECONDS: f32 = 3.0;
+
+// Loads and saves achievements, shows a notice on unlock and lists them all on
+// a screen opened from the Game Over screen
+pub struct AchievementBoardPlugin;
+
+impl Plugin for AchievementBoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AchievementsMenu>()
+            .add_systems(
+                Startup,
+                (load_achievements, spawn_achievements_ui, spawn_achievement_toast),
+            )
+            .add_systems(
+                Update,
+                (
+                    save_achievements,
+                    show_achievement_toast,
+                    (toggle_achievements, display_achievements_screen).chain(),
+                )
+                    .in_set(GameSet::Ui),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct AchievementsMenu {
+    pub open: bool,
+}
+
+pub fn load_achievements(mut commands: Commands) {
+    if let Some(achievements) = persistence::load::<Achievements>(ACHIEVEMENTS_FILE) {
+        commands.insert_resource(achievements);
+    }
+}
+
+pub fn save_achievements(achievements: Res<Achievements>, mut unlocked_events: EventReader<AchievementUnlocked>) {
+    if unlocked_events.iter().count() > 0 {
+        persistence::save(ACHIEVEMENTS_FILE, &*achievements);
+    }
+}
+
+pub fn spawn_achievement_toast(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 28.0,
+                    color: Color::GOLD,
+                    ..default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Percent(12.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        AchievementToast {
+            timer: Timer::from_seconds(TOAST_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+pub fn show_achievement_toast(
+    time: Res<Time>,
+    mut unlocked_events: EventReader<AchievementUnlocked>,
+    mut toast_query: Query<(&mut AchievementToast, &mut Text, &mut Visibility)>,
+) {
+    let Ok((mut toast, mut text, mut visibility)) = toast_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(event) = unlocked_events.iter().last() {
+        text.sections[0].value = format!(
+            "Achievement unlocked: {}\n{}",
+            event.achievement.name(),
+            event.achievement.description()
+        );
+        *visibility = Visibility::Visible;
+        toast.timer.reset();
+    }
+
+    if toast.timer.tick(time.delta()).just_finished() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub fn spawn_achievements_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.9)),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            AchievementsUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Achievements",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            });
+
+            // One line per achievement, filled in by display_achievements_screen
+            parent.spawn((
+                TextBundle {
+                    text: Text::default(),
+                    style: Style {
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                AchievementsText,
+            ));
+
+            // Instructions
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "[H] Back  [R] Restart",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+}
+
+pub fn toggle_achievements(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_over: Res<GameOver>,
+    in_menu: Res<InMenu>,
+    garage: Res<GarageMenu>,
+    stats: Res<StatsMenu>,
+    mut menu: ResMut<AchievementsMenu>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !game_over.0 {
+        // Restarting always leaves the screen
+        if menu.open {
+            menu.open = false;
+        }
+    } else if keyboard_input.just_pressed(KeyCode::H) && !garage.open && !stats.open && !in_menu.0 {
+        menu.open = !menu.open;
+        click_events.send(UiClick);
+    }
+}
+
+pub fn display_achievements_screen(
+    menu: Res<AchievementsMenu>,
+    achievements: Res<Achievements>,
+    settings: Res<Settings>,
+    mut ui_query: Query<&mut Visibility, With<AchievementsUI>>,
+    mut text_query: Query<&mut Text, With<AchievementsText>>,
+) {
+    if !menu.is_changed() && !achievements.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    if let Ok(mut visibility) = ui_query.get_single_mut() {
+        *visibility = if menu.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let palette = settings.gameplay.palette();
+        text.sections = Achievement::ALL
+            .iter()
+            .map(|achievement| {
+                let unlocked = achievements.is_unlocked(*achievement);
+                TextSection::new(
+                    format!(
+                        "{} {:<14} {}\n",
+                        if unlocked { "[x]" } else { "[ ]" },
+                        achievement.name(),
+                        achievement.description()
+                    ),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: if unlocked { palette.highlight } else { Color::GRAY },
+                        ..default()
+                    },
+                )
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::prelude::AudioSource;
+use crate::components::{Hazard, Player, Police, Velocity};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::HALF_WIDTH;
+use crate::persistence;
+use crate::resources::{GameOver, GameSpeed, InMenu, Paused, Settings, SETTINGS_FILE};
+use crate::systems::{CoinCollected, Crash, HazardHit, NearMiss, PowerUpCollected, ShieldHit};
+use crate::ui::UiClick;
+
+#[derive(Resource)]
+pub struct SfxChannel;
+
+#[derive(Resource, Default)]
+pub struct SfxAssets {
+    pub engine: Handle<AudioSource>,
+    pub crash: Handle<AudioSource>,
+    pub whoosh: Handle<AudioSource>,
+    pub click: Handle<AudioSource>,
+    pub power_up: Handle<AudioSource>,
+    pub coin: Handle<AudioSource>,
+    pub siren: Handle<AudioSource>,
+}
+
+// The looping engine sound of the current run
+#[derive(Resource, Default)]
+pub struct EngineSound(pub Handle<AudioInstance>);
+
+// The looping siren while police are chasing
+#[derive(Resource, Default)]
+pub struct SirenSound(pub Option<Handle<AudioInstance>>);
+
+// Kira panning runs from 0.0 (left) to 1.0 (right)
+fn panning_for(x: f32) -> f64 {
+    (0.5 + x / HALF_WIDTH / 2.0).clamp(0.0, 1.0) as f64
+}
+
+pub struct AudioFxPlugin;
+
+impl Plugin for AudioFxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<SfxChannel>()
+            .init_resource::<EngineSound>()
+            .init_resource::<SirenSound>()
+            .add_systems(Startup, load_sfx)
+            .add_systems(RunSetup, start_engine_sound)
+            .add_systems(Update, toggle_mute.in_set(GameSet::Input))
+            .add_systems(
+                Update,
+                (apply_sfx_volume, update_engine_sound, update_siren, play_gameplay_sfx).in_set(GameSet::Ui),
+            );
+    }
+}
+
+pub fn toggle_mute(keyboard_input: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keyboard_input.just_pressed(KeyCode::M) {
+        settings.audio.muted = !settings.audio.muted;
+        persistence::save(SETTINGS_FILE, &*settings);
+    }
+}
+
+pub fn apply_sfx_volume(
+    settings: Res<Settings>,
+    sfx_channel: Res<AudioChannel<SfxChannel>>,
+    mut last_volume: Local<Option<f64>>,
+) {
+    let volume = settings.audio.sfx_volume();
+    if *last_volume != Some(volume) {
+        sfx_channel.set_volume(volume);
+        *last_volume = Some(volume);
+    }
+}
+
+pub fn load_sfx(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(SfxAssets {
+        engine: asset_server.load("audio/engine_loop.wav"),
+        crash: asset_server.load("audio/crash.wav"),
+        whoosh: asset_server.load("audio/whoosh.wav"),
+        click: asset_server.load("audio/click.wav"),
+        power_up: asset_server.load("audio/powerup.wav"),
+        coin: asset_server.load("audio/coin.wav"),
+        siren: asset_server.load("audio/siren.wav"),
+    });
+}
+
+pub fn start_engine_sound(
+    sfx: Res<SfxAssets>,
+    sfx_channel: Res<AudioChannel<SfxChannel>>,
+    mut engine: ResMut<EngineSound>,
+) {
+    sfx_channel.stop();
+    engine.0 = sfx_channel.play(sfx.engine.clone()).looped().handle();
+}
+
+// Engine pitch follows the player's speed and the game speed, and pans with the car
+#[allow(clippy::too_many_arguments)]
+pub fn update_engine_sound(
+    game_over: Res<GameOver>,
+    paused: Res<Paused>,
+    in_menu: Res<InMenu>,
+    game_speed: Res<GameSpeed>,
+    engine: Res<EngineSound>,
+    player_query: Query<(&Transform, &Velocity), With<Player>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut was_running: Local<bool>,
+) {
+    let Some(instance) = instances.get_mut(&engine.0) else {
+        return;
+    };
+
+    let running = !game_over.0 && !paused.0 && !in_menu.0;
+    if running != *was_running {
+        if running {
+            instance.resume(AudioTween::default());
+        } else {
+            instance.pause(AudioTween::default());
+        }
+        *was_running = running;
+    }
+
+    if let Ok((transform, velocity)) = player_query.get_single() {
+        let rate = 0.7 + 0.3 * game_speed.multiplier + (velocity.speed - 150.0) / 1000.0;
+        instance.set_playback_rate(rate.clamp(0.5, 2.5) as f64, AudioTween::default());
+        instance.set_panning(panning_for(transform.translation.x), AudioTween::default());
+    }
+}
+
+// Sirens wail while police are on the player's tail and the run is going
+#[allow(clippy::too_many_arguments)]
+pub fn update_siren(
+    game_over: Res<GameOver>,
+    paused: Res<Paused>,
+    in_menu: Res<InMenu>,
+    sfx: Res<SfxAssets>,
+    sfx_channel: Res<AudioChannel<SfxChannel>>,
+    police_query: Query<(), With<Police>>,
+    mut siren: ResMut<SirenSound>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+) {
+    let chasing = !police_query.is_empty() && !game_over.0 && !paused.0 && !in_menu.0;
+    match (&siren.0, chasing) {
+        (None, true) => siren.0 = Some(sfx_channel.play(sfx.siren.clone()).looped().handle()),
+        (Some(handle), false) => {
+            if let Some(instance) = instances.get_mut(handle) {
+                instance.stop(AudioTween::default());
+            }
+            siren.0 = None;
+        }
+        _ => {}
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_gameplay_sfx(
+    sfx: Res<SfxAssets>,
+    settings: Res<Settings>,
+    sfx_channel: Res<AudioChannel<SfxChannel>>,
+    mut crash_events: EventReader<Crash>,
+    mut shield_events: EventReader<ShieldHit>,
+    mut near_miss_events: EventReader<NearMiss>,
+    mut power_up_events: EventReader<PowerUpCollected>,
+    mut coin_events: EventReader<CoinCollected>,
+    mut hazard_events: EventReader<HazardHit>,
+    mut click_events: EventReader<UiClick>,
+) {
+    for crash in crash_events.iter() {
+        sfx_channel
+            .play(sfx.crash.clone())
+            .with_panning(panning_for(crash.position.x));
+    }
+    for hit in shield_events.iter() {
+        sfx_channel
+            .play(sfx.crash.clone())
+            .with_panning(panning_for(hit.position.x));
+    }
+    for near_miss in near_miss_events.iter() {
+        sfx_channel
+            .play(sfx.whoosh.clone())
+            .with_panning(panning_for(near_miss.position.x));
+    }
+    for power_up in power_up_events.iter() {
+        sfx_channel
+            .play(sfx.power_up.clone())
+            .with_panning(panning_for(power_up.position.x));
+    }
+    for coin in coin_events.iter() {
+        sfx_channel
+            .play(sfx.coin.clone())
+            .with_panning(panning_for(coin.position.x));
+    }
+    // Oil hisses past, potholes and cones get a quieter thump. A volume set on
+    // the sound replaces the channel's, so it is scaled by the SFX volume here.
+    for hit in hazard_events.iter() {
+        let (sound, volume) = match hit.kind {
+            Hazard::OilSlick => (&sfx.whoosh, 1.0),
+            _ => (&sfx.crash, 0.4),
+        };
+        sfx_channel
+            .play(sound.clone())
+            .with_volume(settings.audio.sfx_volume() * volume)
+            .with_panning(panning_for(hit.position.x));
+    }
+    for _ in click_events.iter() {
+        sfx_channel.play(sfx.click.clone());
+    }
+}
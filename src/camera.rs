@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use rand::Rng;
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::PLAYFIELD_SIZE;
+use crate::resources::Settings;
+use crate::components::Hazard;
+use crate::systems::{Crash, HazardHit, ShieldHit};
+
+// Largest camera offset in world units at full trauma
+const MAX_SHAKE_OFFSET: f32 = 12.0;
+// Trauma lost per second
+const SHAKE_DECAY: f32 = 1.5;
+const CRASH_TRAUMA: f32 = 0.8;
+const SHIELD_HIT_TRAUMA: f32 = 0.4;
+const POTHOLE_TRAUMA: f32 = 0.3;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::BLACK))
+            .init_resource::<ScreenShake>()
+            .add_systems(Startup, spawn_camera)
+            .add_systems(RunSetup, reset_screen_shake)
+            .add_systems(Update, shake_camera.in_set(GameSet::Ui));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    pub trauma: f32,
+}
+
+// Always shows the whole playfield, scaled to fit the window. The black clear
+// color letterboxes whichever axis has room to spare.
+pub fn spawn_camera(mut commands: Commands) {
+    let mut camera = Camera2dBundle::default();
+    camera.projection.scaling_mode = ScalingMode::AutoMin {
+        min_width: PLAYFIELD_SIZE.x,
+        min_height: PLAYFIELD_SIZE.y,
+    };
+    commands.spawn(camera);
+}
+
+pub fn reset_screen_shake(mut shake: ResMut<ScreenShake>) {
+    shake.trauma = 0.0;
+}
+
+pub fn shake_camera(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut shake: ResMut<ScreenShake>,
+    mut crash_events: EventReader<Crash>,
+    mut shield_events: EventReader<ShieldHit>,
+    mut hazard_events: EventReader<HazardHit>,
+    mut camera_query: Query<&mut Transform, With<Camera2d>>,
+) {
+    let mut trauma = crash_events.iter().count() as f32 * CRASH_TRAUMA;
+    trauma += shield_events.iter().count() as f32 * SHIELD_HIT_TRAUMA;
+    for hit in hazard_events.iter() {
+        trauma += match hit.kind {
+            Hazard::Pothole => POTHOLE_TRAUMA,
+            Hazard::OilSlick | Hazard::Cone | Hazard::Barrier => 0.0,
+        };
+    }
+    if trauma > 0.0 && settings.gameplay.screen_shake {
+        shake.trauma = (shake.trauma + trauma).min(1.0);
+    }
+    shake.trauma = (shake.trauma - SHAKE_DECAY * time.delta_seconds()).max(0.0);
+
+    let Ok(mut transform) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    // Squaring the trauma makes the shake fall off smoothly
+    let strength = MAX_SHAKE_OFFSET * shake.trauma * shake.trauma;
+    let mut rng = rand::rng();
+    transform.translation.x = rng.random_range(-1.0..=1.0) * strength;
+    transform.translation.y = rng.random_range(-1.0..=1.0) * strength;
+}
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 #[derive(Component)]
 pub struct Player;
@@ -6,12 +7,51 @@ pub struct Player;
 #[derive(Component)]
 pub struct Enemy;
 
+// Enemy driving toward the player in an oncoming lane
+#[derive(Component)]
+pub struct Oncoming;
+
+// Lane a car keeps to, counted from the left
+#[derive(Component)]
+pub struct Lane(pub usize);
+
 #[derive(Component)]
 pub struct Road;
 
+// Dashes between two lanes. `boundary` counts lane boundaries from the left edge.
 #[derive(Component)]
 pub struct RoadLine {
     pub speed: f32,
+    pub boundary: usize,
+}
+
+// One horizontal slice of the road texture; slices follow the road's curves
+#[derive(Component)]
+pub struct RoadStrip {
+    pub index: usize,
+}
+
+// Slice of gravel beside a road strip, on the left (-1.0) or right (1.0)
+#[derive(Component)]
+pub struct Shoulder {
+    pub side: f32,
+}
+
+// Roadside decoration. Scrolls at `parallax` times the road speed and wraps up by
+// `span` once it leaves the bottom of the screen. Stays `offset` outside the
+// shoulder on its `side` of the road.
+#[derive(Component)]
+pub struct Scenery {
+    pub parallax: f32,
+    pub span: f32,
+    pub side: f32,
+    pub offset: f32,
+}
+
+// Sign warning that a lane ends ahead. Fixed to a distance along the road.
+#[derive(Component)]
+pub struct WarningSign {
+    pub distance: f32,
 }
 
 #[derive(Component)]
@@ -19,12 +59,118 @@ pub struct Velocity {
     pub speed: f32,
 }
 
+// Sideways speed of the player's car, which lags behind the steering when grip is low
+#[derive(Component, Default)]
+pub struct LateralVelocity(pub f32);
+
+// Seconds left before the tyres grip again after an oil slick
+#[derive(Component, Default)]
+pub struct Skid(pub f32);
+
 #[derive(Component)]
 pub struct GameOverUI;
 
+#[derive(Component)]
+pub struct GameOverTitle;
+
 #[derive(Component)]
 pub struct GameOverStats;
 
+#[derive(Component)]
+pub struct PauseUI;
+
+#[derive(Component)]
+pub struct OptionsUI;
+
+// "Stage N" title shown for a moment when a stage starts
+#[derive(Component)]
+pub struct StageBanner {
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct OptionsText;
+
+#[derive(Component)]
+pub struct GarageUI;
+
+// The active missions, listed on the pause and Game Over screens
+#[derive(Component)]
+pub struct MissionList;
+
+// Mission progress in the corner of the screen while driving
+#[derive(Component)]
+pub struct MissionHud;
+
+#[derive(Component)]
+pub struct AchievementsUI;
+
+#[derive(Component)]
+pub struct AchievementsText;
+
+// Line across the road at a time-trial checkpoint, the last one being the finish
+#[derive(Component)]
+pub struct Checkpoint {
+    pub index: usize,
+}
+
+// Split time against the best, shown for a moment at each checkpoint
+#[derive(Component)]
+pub struct SplitNotice {
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct MainMenuUI;
+
+#[derive(Component)]
+pub struct MainMenuText;
+
+#[derive(Component)]
+pub struct StatsUI;
+
+#[derive(Component)]
+pub struct StatsText;
+
+// Notice shown for a moment when an achievement unlocks
+#[derive(Component)]
+pub struct AchievementToast {
+    pub timer: Timer,
+}
+
+// "Mission complete" notice shown for a moment after a mission is done
+#[derive(Component)]
+pub struct MissionToast {
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct GarageText;
+
+// Score and active power-ups in the corner of the screen
+#[derive(Component)]
+pub struct HudText;
+
+// Enemy that has already passed the player, so it is only checked once for a near miss
+#[derive(Component)]
+pub struct Overtaken;
+
+// Coin lying on the road, banked into the wallet when the run ends
+#[derive(Component)]
+pub struct Coin;
+
+// Crashing into it is like crashing into a car
+#[derive(Component)]
+pub struct Solid;
+
+// Hazard that has already affected the player, so it only does so once
+#[derive(Component)]
+pub struct Spent;
+
+// Item lying on the road. Scrolls with the road and is pulled in by the magnet.
+#[derive(Component)]
+pub struct Pickup;
+
 #[derive(Component)]
 pub struct Explosion {
     pub timer: Timer,
@@ -37,7 +183,7 @@ pub struct CollisionBounds {
 }
 
 // Car type enum to identify different car models
-#[derive(Component, Clone, Copy, PartialEq)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum CarType {
     GreyPickupTruck,    
     TealSedan,          
@@ -46,11 +192,87 @@ pub enum CarType {
     GreenHatchback,     
     RedSportscar,       
     Player,             
+    Police,
 
 }
 
 
 impl CarType {
+    pub const ALL: [CarType; 8] = [
+        CarType::GreyPickupTruck,
+        CarType::TealSedan,
+        CarType::YellowTaxi,
+        CarType::RedConvertible,
+        CarType::GreenHatchback,
+        CarType::RedSportscar,
+        CarType::Player,
+        CarType::Police,
+    ];
+
+    // Cars the player can drive, in the order the garage lists them
+    pub const GARAGE: [CarType; 7] = [
+        CarType::Player,
+        CarType::TealSedan,
+        CarType::GreyPickupTruck,
+        CarType::YellowTaxi,
+        CarType::GreenHatchback,
+        CarType::RedConvertible,
+        CarType::RedSportscar,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CarType::GreyPickupTruck => "Grey Pickup",
+            CarType::TealSedan => "Teal Sedan",
+            CarType::YellowTaxi => "Yellow Taxi",
+            CarType::RedConvertible => "Red Convertible",
+            CarType::GreenHatchback => "Green Hatchback",
+            CarType::RedSportscar => "Red Sportscar",
+            CarType::Player => "Starter",
+            CarType::Police => "Police",
+        }
+    }
+
+    // Coins to buy the car in the garage
+    pub fn price(&self) -> u32 {
+        match self {
+            CarType::Player | CarType::Police => 0,
+            CarType::TealSedan => 150,
+            CarType::GreyPickupTruck => 200,
+            CarType::YellowTaxi => 250,
+            CarType::GreenHatchback => 350,
+            CarType::RedConvertible => 500,
+            CarType::RedSportscar => 750,
+        }
+    }
+
+    pub fn stats(&self) -> CarStats {
+        let (handling, acceleration, top_speed) = match self {
+            CarType::GreyPickupTruck => (0.7, 450.0, 380.0),
+            CarType::TealSedan => (1.0, 600.0, 420.0),
+            CarType::YellowTaxi => (1.1, 600.0, 400.0),
+            CarType::RedConvertible => (1.1, 750.0, 480.0),
+            CarType::GreenHatchback => (1.3, 550.0, 380.0),
+            CarType::RedSportscar => (1.2, 850.0, 520.0),
+            CarType::Player | CarType::Police => (1.0, 600.0, 400.0),
+        };
+        CarStats {
+            handling,
+            acceleration,
+            top_speed,
+        }
+    }
+
+    // The player sprite is drawn at a higher resolution than the traffic, and
+    // the police sprite at its size on screen
+    pub fn sprite_scale(&self) -> f32 {
+        match self {
+            CarType::Player => 0.1,
+            CarType::Police => 1.0,
+            _ => 0.2,
+        }
+    }
+
     // Custom collision bounds for each car type based on the assets
     pub fn collision_bounds(&self) -> Vec2 {
         match self {
@@ -63,6 +285,7 @@ impl CarType {
             CarType::RedSportscar => Vec2::new(47.0,100.0),
             // Player car
             CarType::Player => Vec2::new(50.0, 120.0),            
+            CarType::Police => Vec2::new(52.0, 104.0),
 
         }
     }
@@ -76,19 +299,189 @@ impl CarType {
             CarType::GreenHatchback => "enemies/enemy5.png",
             CarType::RedSportscar => "enemies/enemy6.png",
             CarType::Player => "player.png",
+            CarType::Police => "police.png",
+        }
+    }
+}
+
+// How the player's car drives
+#[derive(Component, Clone, Copy, Debug)]
+pub struct CarStats {
+    // Scales how quickly the steering takes hold
+    pub handling: f32,
+    // Speed gained per second while accelerating
+    pub acceleration: f32,
+    pub top_speed: f32,
+}
+
+// Extra crashes the player's car can take before one ends the run
+#[derive(Component)]
+pub struct Armor(pub u32);
+
+// Full screen layer darkening the playfield for the time of day
+#[derive(Component)]
+pub struct DarknessOverlay;
+
+// Full screen haze for fog, rain and snow, thickest toward the top
+#[derive(Component)]
+pub struct HazeOverlay;
+
+// Light cone in front of the player's car, shown as it gets dark
+#[derive(Component)]
+pub struct Headlights;
+
+// Raindrop or snowflake. Only the first ones by index are shown in light weather.
+#[derive(Component)]
+pub struct Precipitation {
+    pub index: usize,
+}
+
+// Police car chasing the player
+#[derive(Component)]
+pub struct Police {
+    pub role: PoliceRole,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PoliceRole {
+    // Drives into the back of the player
+    Rammer,
+    // Pulls alongside, a lane to the left (-1.0) or right (1.0), to box the player in
+    Blocker { side: f32 },
+}
+
+// Flashing light bar on a police car
+#[derive(Component)]
+pub struct SirenLight;
+
+// Translucent replay of the personal best run, never collides
+#[derive(Component)]
+pub struct Ghost;
+
+// Belongs to the current run: despawned when a new run starts
+#[derive(Component)]
+pub struct RunScoped;
+
+#[derive(Component, Clone, Copy, PartialEq, Deserialize)]
+pub enum SceneryKind {
+    Tree,
+    Barrier,
+    Building,
+    Cactus,
+    Rock,
+}
+
+impl SceneryKind {
+    pub const ALL: [SceneryKind; 5] = [
+        SceneryKind::Tree,
+        SceneryKind::Barrier,
+        SceneryKind::Building,
+        SceneryKind::Cactus,
+        SceneryKind::Rock,
+    ];
+
+    pub fn size(&self) -> Vec2 {
+        match self {
+            SceneryKind::Tree => Vec2::new(64.0, 64.0),
+            SceneryKind::Barrier => Vec2::new(24.0, 64.0),
+            SceneryKind::Building => Vec2::new(96.0, 128.0),
+            SceneryKind::Cactus => Vec2::new(40.0, 56.0),
+            SceneryKind::Rock => Vec2::new(40.0, 32.0),
+        }
+    }
+
+    pub fn asset_path(&self) -> &'static str {
+        match self {
+            SceneryKind::Tree => "scenery/tree.png",
+            SceneryKind::Barrier => "scenery/barrier.png",
+            SceneryKind::Building => "scenery/building.png",
+            SceneryKind::Cactus => "scenery/cactus.png",
+            SceneryKind::Rock => "scenery/rock.png",
+        }
+    }
+}
+
+// Power-up pickup, and the timed effect it gives once collected
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum PowerUp {
+    // Absorbs one crash
+    Shield,
+    // Faster, and scores double
+    Nitro,
+    // Slows the whole road down
+    SlowMo,
+    // Pulls nearby pickups in
+    Magnet,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 4] = [PowerUp::Shield, PowerUp::Nitro, PowerUp::SlowMo, PowerUp::Magnet];
+
+    // Seconds the effect lasts
+    pub fn duration(&self) -> f32 {
+        match self {
+            PowerUp::Shield => 10.0,
+            PowerUp::Nitro => 4.0,
+            PowerUp::SlowMo => 5.0,
+            PowerUp::Magnet => 8.0,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            PowerUp::Shield => "Shield",
+            PowerUp::Nitro => "Nitro",
+            PowerUp::SlowMo => "Slow-mo",
+            PowerUp::Magnet => "Magnet",
+        }
+    }
+
+    pub fn asset_path(&self) -> &'static str {
+        match self {
+            PowerUp::Shield => "powerups/shield.png",
+            PowerUp::Nitro => "powerups/nitro.png",
+            PowerUp::SlowMo => "powerups/slow_mo.png",
+            PowerUp::Magnet => "powerups/magnet.png",
+        }
+    }
+}
+
+// Static obstacle lying on the road
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub enum Hazard {
+    // Steering stops working for a moment
+    OilSlick,
+    // Jolts the car and costs a point of armor, or wrecks it with none left
+    Pothole,
+    // Closes a lane at roadworks, as solid as a car
+    Cone,
+    // Closes a lane at roadworks, as solid as a car
+    Barrier,
+}
+
+impl Hazard {
+    pub const ALL: [Hazard; 4] = [Hazard::OilSlick, Hazard::Pothole, Hazard::Cone, Hazard::Barrier];
+
+    // Solid hazards block their lane and are left to check_collision
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Hazard::Cone | Hazard::Barrier)
+    }
+
+    pub fn size(&self) -> Vec2 {
+        match self {
+            Hazard::OilSlick => Vec2::new(48.0, 40.0),
+            Hazard::Pothole => Vec2::new(36.0, 28.0),
+            Hazard::Cone => Vec2::new(20.0, 24.0),
+            Hazard::Barrier => Vec2::new(60.0, 20.0),
         }
     }
 
-    // Map enemy index to car type
-    pub fn from_enemy_index(index: u32) -> Self {
-        match index {
-            1 => CarType::GreyPickupTruck,
-            2 => CarType::TealSedan,
-            3 => CarType::YellowTaxi,
-            4 => CarType::RedConvertible,
-            5 => CarType::GreenHatchback,
-            6 => CarType::RedSportscar,
-            _ => CarType::TealSedan, // Default fallback
+    pub fn asset_path(&self) -> &'static str {
+        match self {
+            Hazard::OilSlick => "hazards/oil.png",
+            Hazard::Pothole => "hazards/pothole.png",
+            Hazard::Cone => "hazards/cone.png",
+            Hazard::Barrier => "hazards/barrier.png",
         }
     }
 }
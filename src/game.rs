@@ -0,0 +1,163 @@
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use crate::components::RunScoped;
+use crate::resources::*;
+use crate::systems::*;
+
+// Order of the gameplay frame. Progress (distance, speed ramp and road layout),
+// movement, collision and cleanup only run while the game isn't over or paused.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    Input,
+    Progress,
+    Movement,
+    Collision,
+    Cleanup,
+    Ui,
+}
+
+// Runs once at startup and again on every restart, after all RunScoped
+// entities are despawned. Plugins add systems here to spawn their run entities
+// and reset their run resources.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunSetup;
+
+// Sent to tear down the current run and start a new one
+#[derive(Event, Default)]
+pub struct RestartRun;
+
+// Shared resources, system set ordering, the speed ramp and the road layout
+// every gameplay plugin relies on
+#[derive(Default)]
+pub struct CorePlugin {
+    pub difficulty: Difficulty,
+}
+
+impl Plugin for CorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.difficulty)
+            .insert_resource(EnemySpawnTimer::new(self.difficulty.spawn_interval()))
+            .insert_resource(GameSpeed::default())
+            .insert_resource(GameOver::default())
+            .insert_resource(Paused::default())
+            .init_resource::<InMenu>()
+            .init_resource::<GameMode>()
+            .init_resource::<Score>()
+            .init_resource::<OncomingTime>()
+            .init_resource::<ActiveEffects>()
+            .init_resource::<RunCoins>()
+            .init_resource::<Profile>()
+            .init_resource::<PlayerInput>()
+            .init_resource::<GameRng>()
+            .init_resource::<GameAssets>()
+            .init_resource::<Stages>()
+            .init_resource::<CurrentStage>()
+            .init_resource::<RoadLayout>()
+            .init_resource::<Roadworks>()
+            .add_event::<RestartRun>()
+            .init_schedule(RunSetup)
+            .configure_sets(
+                Update,
+                (
+                    GameSet::Input,
+                    GameSet::Progress,
+                    GameSet::Movement,
+                    GameSet::Collision,
+                    GameSet::Cleanup,
+                    GameSet::Ui,
+                )
+                    .chain(),
+            )
+            .configure_set(Update, GameSet::Progress.run_if(game_running))
+            .configure_set(Update, GameSet::Movement.run_if(game_running))
+            .configure_set(Update, GameSet::Collision.run_if(game_running))
+            .configure_set(Update, GameSet::Cleanup.run_if(game_running))
+            .add_systems(PostStartup, start_run)
+            .add_systems(RunSetup, reset_run_resources)
+            .add_systems(
+                Update,
+                update_game_speed.in_set(GameSet::Progress),
+            )
+            .add_systems(Update, handle_restart.after(GameSet::Ui));
+    }
+}
+
+// The single reset path: tear down everything from the last run and set up a new one
+pub fn start_run(world: &mut World) {
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, With<RunScoped>>()
+        .iter(world)
+        .collect();
+    for entity in entities {
+        despawn_with_children_recursive(world, entity);
+    }
+
+    world.run_schedule(RunSetup);
+}
+
+pub fn handle_restart(world: &mut World) {
+    let restart_requested = world.resource_mut::<Events<RestartRun>>().drain().count() > 0;
+    if restart_requested {
+        start_run(world);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn reset_run_resources(
+    mut game_speed: ResMut<GameSpeed>,
+    mut score: ResMut<Score>,
+    mut oncoming_time: ResMut<OncomingTime>,
+    mut effects: ResMut<ActiveEffects>,
+    mut run_coins: ResMut<RunCoins>,
+    mut game_over: ResMut<GameOver>,
+    mut paused: ResMut<Paused>,
+    mut enemy_timer: ResMut<EnemySpawnTimer>,
+    mut input: ResMut<PlayerInput>,
+    mut current_stage: ResMut<CurrentStage>,
+    mut road_layout: ResMut<RoadLayout>,
+    mut roadworks: ResMut<Roadworks>,
+) {
+    *game_speed = GameSpeed::default();
+    score.0 = 0.0;
+    oncoming_time.0 = 0.0;
+    effects.clear();
+    run_coins.0 = 0;
+    game_over.0 = false;
+    paused.0 = false;
+    enemy_timer.0.reset();
+    *input = PlayerInput::default();
+    current_stage.0 = 0;
+    road_layout.reset();
+    roadworks.clear();
+}
+
+// Gameplay logic only: no window, rendering, audio or keyboard required, so it
+// also runs under MinimalPlugins for headless simulation.
+#[derive(Default)]
+pub struct GamePlugin {
+    pub difficulty: Difficulty,
+}
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            CorePlugin {
+                difficulty: self.difficulty,
+            },
+            StagePlugin,
+            WeatherPlugin,
+            RoadPlugin,
+            TrafficPlugin,
+            PowerUpPlugin,
+            CoinPlugin,
+            HazardPlugin,
+            PolicePlugin,
+            MissionPlugin,
+            StatsPlugin,
+            AchievementPlugin,
+            TimeTrialPlugin,
+            PlayerPlugin,
+            CollisionPlugin,
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+use bevy::prelude::*;
+use crate::achievements::AchievementsMenu;
+use crate::components::{CarType, GarageText, GarageUI};
+use crate::game::GameSet;
+use crate::persistence;
+use crate::playfield::UNITS_PER_METER;
+use crate::resources::{GameOver, GameSpeed, InMenu, Profile, RunCoins, Settings, Upgrade, PROFILE_FILE, UPGRADE_PRICES};
+use crate::stats::StatsMenu;
+use crate::ui::UiClick;
+
+// Keys that buy the next tier of each upgrade, in Upgrade::ALL order
+const UPGRADE_KEYS: [KeyCode; 4] = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+
+pub struct GaragePlugin;
+
+impl Plugin for GaragePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GarageMenu>()
+            .add_systems(Startup, (load_profile, spawn_garage_ui))
+            .add_systems(
+                Update,
+                (
+                    bank_run_coins,
+                    (toggle_garage, navigate_garage, display_garage_screen).chain(),
+                )
+                    .in_set(GameSet::Ui),
+            );
+    }
+}
+
+// Garage overlay, opened from the Game Over screen
+#[derive(Resource, Default)]
+pub struct GarageMenu {
+    pub open: bool,
+    pub selected: usize,
+}
+
+pub fn load_profile(mut commands: Commands) {
+    let profile: Profile = persistence::load(PROFILE_FILE).unwrap_or_default();
+    commands.insert_resource(profile);
+}
+
+// Pays the coins picked up and the distance bonus into the wallet when a run ends
+pub fn bank_run_coins(
+    game_over: Res<GameOver>,
+    game_speed: Res<GameSpeed>,
+    run_coins: Res<RunCoins>,
+    mut profile: ResMut<Profile>,
+) {
+    if game_over.is_changed() && game_over.0 {
+        profile.coins += run_coins.earned(game_speed.distance / UNITS_PER_METER);
+        persistence::save(PROFILE_FILE, &*profile);
+    }
+}
+
+pub fn spawn_garage_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.9)),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            GarageUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Garage",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            });
+
+            // Wallet, then one section per car, filled in by display_garage_screen
+            parent.spawn((
+                TextBundle {
+                    text: Text::default(),
+                    style: Style {
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                GarageText,
+            ));
+
+            // Instructions
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "[Up/Down] Select  [Enter] Buy or Drive  [1-4] Upgrade  [G] Back  [R] Restart",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+}
+
+pub fn toggle_garage(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_over: Res<GameOver>,
+    in_menu: Res<InMenu>,
+    achievements: Res<AchievementsMenu>,
+    stats: Res<StatsMenu>,
+    mut menu: ResMut<GarageMenu>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !game_over.0 {
+        // Restarting always leaves the garage
+        if menu.open {
+            menu.open = false;
+        }
+    } else if keyboard_input.just_pressed(KeyCode::G) && !achievements.open && !stats.open && !in_menu.0 {
+        menu.open = !menu.open;
+        click_events.send(UiClick);
+    }
+}
+
+pub fn navigate_garage(
+    keyboard_input: Res<Input<KeyCode>>,
+    in_menu: Res<InMenu>,
+    mut menu: ResMut<GarageMenu>,
+    mut profile: ResMut<Profile>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !menu.open || in_menu.0 {
+        return;
+    }
+
+    let row_count = CarType::GARAGE.len();
+    if keyboard_input.any_just_pressed([KeyCode::Up, KeyCode::W]) {
+        menu.selected = (menu.selected + row_count - 1) % row_count;
+        click_events.send(UiClick);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Down, KeyCode::S]) {
+        menu.selected = (menu.selected + 1) % row_count;
+        click_events.send(UiClick);
+    }
+
+    // Enter drives an owned car, or buys one the wallet can pay for
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        let car_type = CarType::GARAGE[menu.selected];
+        if profile.owns(car_type) || profile.buy(car_type) {
+            profile.selected_car = car_type;
+            persistence::save(PROFILE_FILE, &*profile);
+            click_events.send(UiClick);
+        }
+    }
+
+    for (key, upgrade) in UPGRADE_KEYS.into_iter().zip(Upgrade::ALL) {
+        if keyboard_input.just_pressed(key) && profile.buy_upgrade(CarType::GARAGE[menu.selected], upgrade) {
+            persistence::save(PROFILE_FILE, &*profile);
+            click_events.send(UiClick);
+        }
+    }
+}
+
+fn car_label(car_type: CarType, profile: &Profile) -> String {
+    let stats = profile.upgrades(car_type).apply(car_type.stats());
+    let hitbox = car_type.collision_bounds();
+    let status = if profile.selected_car == car_type {
+        "Driving".to_string()
+    } else if profile.owns(car_type) {
+        "Owned".to_string()
+    } else {
+        format!("{} coins", car_type.price())
+    };
+    format!(
+        "{:<16} Handling {:.1}  Accel {:>3}  Top {:>3}  Hitbox {}x{}  {}",
+        car_type.name(),
+        stats.handling,
+        stats.acceleration,
+        stats.top_speed,
+        hitbox.x,
+        hitbox.y,
+        status
+    )
+}
+
+// Tier bars and the next tier's price for an owned car
+fn upgrade_label(index: usize, upgrade: Upgrade, car_type: CarType, profile: &Profile) -> String {
+    let tiers = profile.upgrades(car_type);
+    let tier = tiers.tier(upgrade);
+    let price = match tiers.next_price(upgrade) {
+        Some(price) => format!("{} coins", price),
+        None => "Maxed".to_string(),
+    };
+    format!(
+        "[{}] {:<13} [{}{}]  {}",
+        index + 1,
+        upgrade.name(),
+        "#".repeat(tier),
+        "-".repeat(UPGRADE_PRICES.len() - tier),
+        price
+    )
+}
+
+pub fn display_garage_screen(
+    menu: Res<GarageMenu>,
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+    mut ui_query: Query<&mut Visibility, With<GarageUI>>,
+    mut text_query: Query<&mut Text, With<GarageText>>,
+) {
+    if !menu.is_changed() && !profile.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    if let Ok(mut visibility) = ui_query.get_single_mut() {
+        *visibility = if menu.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let palette = settings.gameplay.palette();
+        let wallet = TextSection::new(
+            format!("Coins: {}\n\n", profile.coins),
+            TextStyle {
+                font_size: 28.0,
+                color: palette.highlight,
+                ..default()
+            },
+        );
+        let rows = CarType::GARAGE.iter().enumerate().map(|(i, car_type)| {
+            let selected = i == menu.selected;
+            let affordable = profile.owns(*car_type) || profile.coins >= car_type.price();
+            let color = match (selected, affordable) {
+                (true, _) => palette.highlight,
+                (false, true) => Color::WHITE,
+                (false, false) => Color::GRAY,
+            };
+            TextSection::new(
+                format!("{} {}\n", if selected { ">" } else { " " }, car_label(*car_type, &profile)),
+                TextStyle {
+                    font_size: 22.0,
+                    color,
+                    ..default()
+                },
+            )
+        });
+        let highlighted = CarType::GARAGE[menu.selected];
+        let upgrades = if profile.owns(highlighted) {
+            Upgrade::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, upgrade)| upgrade_label(i, *upgrade, highlighted, &profile))
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            "Buy this car to upgrade it".to_string()
+        };
+        let upgrades = TextSection::new(
+            format!("\n{} upgrades\n{}", highlighted.name(), upgrades),
+            TextStyle {
+                font_size: 22.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        );
+        text.sections = std::iter::once(wallet).chain(rows).chain(std::iter::once(upgrades)).collect();
+    }
+}
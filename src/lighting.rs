@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::components::{DarknessOverlay, HazeOverlay, Headlights, Player, Precipitation, RunScoped};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::{HALF_HEIGHT, HALF_WIDTH, PLAYFIELD_SIZE};
+use crate::resources::{Atmosphere, Weather};
+
+// There is no 3D lighting, so light and weather are drawn as layers over the
+// playfield: precipitation, then haze, then darkness, with the headlights on top
+const PRECIPITATION_Z: f32 = 18.0;
+const HAZE_Z: f32 = 19.0;
+const DARKNESS_Z: f32 = 20.0;
+const HEADLIGHTS_Z: f32 = 21.0;
+
+const DARKNESS_MAX_ALPHA: f32 = 0.7;
+const DUSK_COLOR: Vec3 = Vec3::new(0.45, 0.2, 0.1);
+const NIGHT_COLOR: Vec3 = Vec3::new(0.02, 0.03, 0.12);
+const HEADLIGHTS_MAX_ALPHA: f32 = 0.45;
+const HEADLIGHTS_SIZE: Vec2 = Vec2::new(110.0, 190.0);
+// From the player's center to the middle of the light cone
+const HEADLIGHTS_OFFSET: f32 = 150.0;
+const PRECIPITATION_COUNT: usize = 160;
+
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_overlays)
+            .add_systems(RunSetup, spawn_headlights)
+            .add_systems(Update, move_precipitation.in_set(GameSet::Movement))
+            .add_systems(Update, (apply_lighting, follow_player_with_headlights).in_set(GameSet::Ui));
+    }
+}
+
+pub fn spawn_overlays(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::NONE,
+                custom_size: Some(PLAYFIELD_SIZE),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, DARKNESS_Z),
+            ..default()
+        },
+        DarknessOverlay,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("lights/fog.png"),
+            sprite: Sprite {
+                color: Color::NONE,
+                custom_size: Some(PLAYFIELD_SIZE),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, HAZE_Z),
+            ..default()
+        },
+        HazeOverlay,
+    ));
+
+    let mut rng = rand::rng();
+    for index in 0..PRECIPITATION_COUNT {
+        commands.spawn((
+            SpriteBundle {
+                transform: Transform::from_xyz(
+                    rng.random_range(-HALF_WIDTH..HALF_WIDTH),
+                    rng.random_range(-HALF_HEIGHT..HALF_HEIGHT),
+                    PRECIPITATION_Z,
+                ),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Precipitation { index },
+        ));
+    }
+}
+
+pub fn spawn_headlights(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("lights/headlight.png"),
+            sprite: Sprite {
+                color: Color::NONE,
+                custom_size: Some(HEADLIGHTS_SIZE),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, HEADLIGHTS_Z),
+            ..default()
+        },
+        Headlights,
+        RunScoped,
+    ));
+}
+
+// How much each weather hides the road ahead at full intensity
+fn haze_alpha(weather: Weather) -> f32 {
+    match weather {
+        Weather::Clear => 0.0,
+        Weather::Rain => 0.25,
+        Weather::Fog => 0.85,
+        Weather::Snow => 0.35,
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn apply_lighting(
+    atmosphere: Res<Atmosphere>,
+    mut darkness_query: Query<&mut Sprite, (With<DarknessOverlay>, Without<HazeOverlay>, Without<Headlights>)>,
+    mut haze_query: Query<&mut Sprite, (With<HazeOverlay>, Without<Headlights>)>,
+    mut headlights_query: Query<&mut Sprite, With<Headlights>>,
+) {
+    let darkness = 1.0 - atmosphere.daylight;
+
+    // Orange at dusk, deep blue at night
+    let night = ((darkness - 0.45) / 0.55).clamp(0.0, 1.0);
+    let color = DUSK_COLOR.lerp(NIGHT_COLOR, night);
+    for mut sprite in darkness_query.iter_mut() {
+        sprite.color = Color::rgba(color.x, color.y, color.z, DARKNESS_MAX_ALPHA * darkness);
+    }
+
+    let haze = haze_alpha(atmosphere.weather) * atmosphere.intensity;
+    for mut sprite in haze_query.iter_mut() {
+        sprite.color = Color::rgba(0.75, 0.77, 0.8, haze);
+    }
+
+    // Headlights come on once it starts getting dark
+    let headlights = HEADLIGHTS_MAX_ALPHA * ((darkness - 0.3) / 0.7).clamp(0.0, 1.0);
+    for mut sprite in headlights_query.iter_mut() {
+        sprite.color = Color::rgba(1.0, 0.95, 0.7, headlights);
+    }
+}
+
+pub fn follow_player_with_headlights(
+    player_query: Query<&Transform, (With<Player>, Without<Headlights>)>,
+    mut headlights_query: Query<&mut Transform, With<Headlights>>,
+) {
+    let Ok(player) = player_query.get_single() else {
+        return;
+    };
+    for mut transform in headlights_query.iter_mut() {
+        transform.translation.x = player.translation.x;
+        transform.translation.y = player.translation.y + HEADLIGHTS_OFFSET;
+    }
+}
+
+// Rain streaks down fast at a slant, snow drifts down swaying. Heavier weather
+// shows more of the pool.
+pub fn move_precipitation(
+    time: Res<Time>,
+    atmosphere: Res<Atmosphere>,
+    mut query: Query<(&mut Transform, &mut Sprite, &mut Visibility, &Precipitation)>,
+) {
+    let (fall_speed, drift, size, color) = match atmosphere.weather {
+        Weather::Rain => (900.0, -140.0, Vec2::new(2.0, 22.0), Color::rgba(0.7, 0.75, 0.9, 0.6)),
+        Weather::Snow => (110.0, 0.0, Vec2::new(5.0, 5.0), Color::rgba(1.0, 1.0, 1.0, 0.9)),
+        Weather::Clear | Weather::Fog => {
+            for (_, _, mut visibility, _) in query.iter_mut() {
+                *visibility = Visibility::Hidden;
+            }
+            return;
+        }
+    };
+
+    let shown = (PRECIPITATION_COUNT as f32 * atmosphere.intensity) as usize;
+    let dt = time.delta_seconds();
+    let elapsed = time.elapsed_seconds();
+    let mut rng = rand::rng();
+    for (mut transform, mut sprite, mut visibility, drop) in query.iter_mut() {
+        *visibility = if drop.index < shown { Visibility::Inherited } else { Visibility::Hidden };
+        sprite.custom_size = Some(size);
+        sprite.color = color;
+
+        let sway = if atmosphere.weather == Weather::Snow {
+            30.0 * (elapsed * 1.5 + drop.index as f32).sin()
+        } else {
+            drift
+        };
+        transform.translation.x += sway * dt;
+        transform.translation.y -= fall_speed * dt;
+        transform.rotation = Quat::from_rotation_z((drift / fall_speed).atan());
+
+        if transform.translation.y < -HALF_HEIGHT {
+            transform.translation.y += PLAYFIELD_SIZE.y;
+            transform.translation.x = rng.random_range(-HALF_WIDTH..HALF_WIDTH);
+        }
+    }
+}
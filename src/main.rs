@@ -1,24 +1,83 @@
 #![windows_subsystem = "windows"]
+mod achievements;
+mod audio;
+mod camera;
 mod components;
+mod game;
+mod garage;
+mod lighting;
+mod menu;
+mod missions;
+mod music;
+mod options;
+mod persistence;
+mod playfield;
 mod resources;
 mod setup;
+mod simulation;
+mod stats;
 mod systems;
+mod time_trial;
 mod ui;
 
-use bevy::{asset::AssetPlugin, prelude::*, window::PresentMode};
+#[cfg(test)]
+mod tests;
+
+use bevy::{asset::AssetPlugin, prelude::*, window::PrimaryWindow};
 use resources::*;
-use setup::setup;
-use setup::play_music;
-use systems::*;
-use ui::{display_game_over_screen, restart_game};
+use achievements::AchievementBoardPlugin;
+use audio::AudioFxPlugin;
+use game::GamePlugin;
+use garage::GaragePlugin;
+use lighting::LightingPlugin;
+use menu::MainMenuPlugin;
+use missions::MissionBoardPlugin;
+use music::MusicPlugin;
+use options::OptionsPlugin;
+use camera::CameraPlugin;
+use setup::load_game_assets;
+use stats::StatsBoardPlugin;
+use systems::GhostPlugin;
+use time_trial::TimeTrialBoardPlugin;
+use ui::UiPlugin;
 use bevy_embedded_assets::EmbeddedAssetPlugin; 
 use bevy_kira_audio::prelude::*;
-use collision::explosion_cleanup_system;
-use setup::set_windows_titlebar_icon;
+use setup::set_window_icon;
 
 // Entry point for the game
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // Headless balance testing: `--simulate [runs] [--difficulty name] [--seed n]`
+    if let Some(config) = simulation::SimulationConfig::from_args(&args) {
+        simulation::attach_console();
+        match config {
+            Ok(config) => simulation::run(config),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let difficulty = match args.iter().position(|arg| arg == "--difficulty") {
+        Some(index) => {
+            let name = args.get(index + 1).map(String::as_str).unwrap_or_default();
+            match Difficulty::from_name(name) {
+                Some(difficulty) => difficulty,
+                None => {
+                    eprintln!("Unknown difficulty '{}', expected easy, normal or hard", name);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => Difficulty::default(),
+    };
+
+    let settings: Settings = persistence::load(SETTINGS_FILE).unwrap_or_default();
+
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -27,8 +86,9 @@ fn main() {
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Real Need For Racing".into(),
-                        resolution: (800., 600.).into(),
-                        present_mode: PresentMode::AutoVsync,
+                        resolution: settings.video.resolution.into(),
+                        mode: settings.video.window_mode(),
+                        present_mode: settings.video.present_mode(),
                         ..default()
                     }),
                     ..default()
@@ -36,31 +96,28 @@ fn main() {
                 .set(AssetPlugin {
                     asset_folder: "assets".into(),
                     watch_for_changes: None,
-                    ..default()
                 }),AudioPlugin)
         )
-        .insert_resource(EnemySpawnTimer::default())
-        .insert_resource(GameSpeed::default())
-        .insert_resource(GameOver::default())
-        .add_systems(Startup, setup)
-        .add_systems(Update, display_game_over_screen)
-        .add_systems(Update, restart_game)
-        .add_systems(
-            Update,
-            (
-                update_game_speed,
-                player_movement,
-                scroll_road_lines,
-                enemy_movement,
-                spawn_enemy_over_time,
-                cleanup_enemies,
-                check_collision, 
-                explosion_cleanup_system,
-            )
-                .run_if(game_not_over),
-        )
-        .add_systems(Startup, play_music) 
-        .add_systems(Startup, set_windows_titlebar_icon)
+        .insert_resource(settings)
+        .add_plugins((
+            GamePlugin { difficulty },
+            CameraPlugin,
+            LightingPlugin,
+            GhostPlugin,
+            UiPlugin,
+            OptionsPlugin,
+            GaragePlugin,
+            MissionBoardPlugin,
+            AchievementBoardPlugin,
+            StatsBoardPlugin,
+            TimeTrialBoardPlugin,
+            MainMenuPlugin,
+            AudioFxPlugin,
+            MusicPlugin,
+        ))
+        .add_systems(PreStartup, load_game_assets)
+        // Only windowed runs have a window to put the icon on
+        .add_systems(Startup, set_window_icon.run_if(any_with_component::<PrimaryWindow>()))
         .run();
 }
 
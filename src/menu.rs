@@ -0,0 +1,204 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use crate::achievements::AchievementsMenu;
+use crate::components::{MainMenuText, MainMenuUI};
+use crate::game::{GameSet, RestartRun};
+use crate::garage::GarageMenu;
+use crate::missions::mission_list;
+use crate::resources::{GameMode, GameOver, InMenu, Settings, TrialRecord};
+use crate::stats::StatsMenu;
+use crate::time_trial::{best_time_label, course_label};
+use crate::ui::UiClick;
+
+// Mode select shown at startup and from the Game Over screen. The game waits
+// behind it until a mode is picked.
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InMenu(true))
+            .init_resource::<MainMenu>()
+            .add_systems(Startup, spawn_main_menu_ui)
+            .add_systems(
+                Update,
+                (navigate_main_menu, open_main_menu, display_main_menu)
+                    .chain()
+                    .in_set(GameSet::Ui),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MainMenu {
+    pub selected: usize,
+}
+
+pub fn spawn_main_menu_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.9)),
+                z_index: ZIndex::Global(20),
+                ..default()
+            },
+            MainMenuUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Real Need For Racing",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            });
+
+            // One row per mode, filled in by display_main_menu
+            parent.spawn((
+                TextBundle {
+                    text: Text::default(),
+                    style: Style {
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                MainMenuText,
+            ));
+
+            parent.spawn(mission_list());
+
+            // Instructions
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "[Up/Down] Select  [Enter] Start  [Esc] Quit",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+}
+
+// Enter starts a fresh run in the selected mode
+pub fn navigate_main_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut in_menu: ResMut<InMenu>,
+    mut menu: ResMut<MainMenu>,
+    mut mode: ResMut<GameMode>,
+    mut restart_events: EventWriter<RestartRun>,
+    mut click_events: EventWriter<UiClick>,
+    mut exit_events: EventWriter<AppExit>,
+) {
+    if !in_menu.0 {
+        return;
+    }
+
+    let row_count = GameMode::ALL.len();
+    if keyboard_input.any_just_pressed([KeyCode::Up, KeyCode::W]) {
+        menu.selected = (menu.selected + row_count - 1) % row_count;
+        click_events.send(UiClick);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Down, KeyCode::S]) {
+        menu.selected = (menu.selected + 1) % row_count;
+        click_events.send(UiClick);
+    }
+
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        *mode = GameMode::ALL[menu.selected];
+        in_menu.0 = false;
+        restart_events.send(RestartRun);
+        click_events.send(UiClick);
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        exit_events.send(AppExit);
+    }
+}
+
+// [Esc] on the Game Over screen goes back to the menu, closing any screen
+// open over it so that one stops taking keys
+pub fn open_main_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_over: Res<GameOver>,
+    mut in_menu: ResMut<InMenu>,
+    mut garage: ResMut<GarageMenu>,
+    mut stats: ResMut<StatsMenu>,
+    mut achievements: ResMut<AchievementsMenu>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if game_over.0 && !in_menu.0 && keyboard_input.just_pressed(KeyCode::Escape) {
+        in_menu.0 = true;
+        garage.open = false;
+        stats.open = false;
+        achievements.open = false;
+        click_events.send(UiClick);
+    }
+}
+
+fn mode_label(mode: GameMode, record: &TrialRecord) -> String {
+    match mode {
+        GameMode::Endless => format!("{:<12} Survive as long as you can", mode.name()),
+        GameMode::TimeTrial => format!("{:<12} {}  {}", mode.name(), course_label(), best_time_label(record)),
+    }
+}
+
+pub fn display_main_menu(
+    in_menu: Res<InMenu>,
+    menu: Res<MainMenu>,
+    record: Res<TrialRecord>,
+    settings: Res<Settings>,
+    mut ui_query: Query<&mut Visibility, With<MainMenuUI>>,
+    mut text_query: Query<&mut Text, With<MainMenuText>>,
+) {
+    if !in_menu.is_changed() && !menu.is_changed() && !record.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    if let Ok(mut visibility) = ui_query.get_single_mut() {
+        *visibility = if in_menu.0 { Visibility::Visible } else { Visibility::Hidden };
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let palette = settings.gameplay.palette();
+        text.sections = GameMode::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, mode)| {
+                let selected = i == menu.selected;
+                TextSection::new(
+                    format!("{} {}\n", if selected { ">" } else { " " }, mode_label(*mode, &record)),
+                    TextStyle {
+                        font_size: 28.0,
+                        color: if selected { palette.highlight } else { Color::WHITE },
+                        ..default()
+                    },
+                )
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,168 @@
+use bevy::prelude::*;
+use crate::components::{MissionHud, MissionList, MissionToast};
+use crate::game::GameSet;
+use crate::persistence;
+use crate::resources::{GameOver, Mission, Missions, Profile, MISSIONS_FILE, PROFILE_FILE};
+use crate::systems::MissionCompleted;
+
+const TOAST_SECONDS: f32 = 2.5;
+
+// Loads and saves the missions, and shows them on the HUD, the pause and Game
+// Over screens and in a notice when one is done
+pub struct MissionBoardPlugin;
+
+impl Plugin for MissionBoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (load_missions, spawn_mission_hud, spawn_mission_toast))
+            .add_systems(
+                Update,
+                (save_missions, update_mission_text, show_mission_toast).in_set(GameSet::Ui),
+            );
+    }
+}
+
+pub fn load_missions(mut commands: Commands) {
+    if let Some(missions) = persistence::load::<Missions>(MISSIONS_FILE) {
+        commands.insert_resource(missions);
+    }
+}
+
+// Saved when a mission pays out and when a run ends, so counts carry over
+pub fn save_missions(
+    game_over: Res<GameOver>,
+    missions: Res<Missions>,
+    profile: Res<Profile>,
+    mut completed_events: EventReader<MissionCompleted>,
+) {
+    let completed = completed_events.iter().count() > 0;
+    if completed {
+        persistence::save(PROFILE_FILE, &*profile);
+    }
+    if completed || (game_over.is_changed() && game_over.0) {
+        persistence::save(MISSIONS_FILE, &*missions);
+    }
+}
+
+// Text listing the active missions, filled in by update_mission_text
+pub fn mission_list() -> (TextBundle, MissionList) {
+    (
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 22.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            style: Style {
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        },
+        MissionList,
+    )
+}
+
+pub fn spawn_mission_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_alignment(TextAlignment::Right),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        },
+        MissionHud,
+    ));
+}
+
+pub fn spawn_mission_toast(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 32.0,
+                    color: Color::GOLD,
+                    ..default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(35.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        MissionToast {
+            timer: Timer::from_seconds(TOAST_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+fn progress_line(mission: &Mission) -> String {
+    let target = mission.kind.target();
+    format!(
+        "{} {}/{}",
+        mission.kind.description(),
+        mission.progress.min(target) as u32,
+        target as u32
+    )
+}
+
+pub fn update_mission_text(
+    missions: Res<Missions>,
+    mut list_query: Query<&mut Text, (With<MissionList>, Without<MissionHud>)>,
+    mut hud_query: Query<&mut Text, (With<MissionHud>, Without<MissionList>)>,
+) {
+    if !missions.is_changed() {
+        return;
+    }
+
+    let lines: Vec<String> = missions.active.iter().map(progress_line).collect();
+    for mut text in list_query.iter_mut() {
+        text.sections[0].value = format!("Missions\n{}", lines.join("\n"));
+    }
+    if let Ok(mut text) = hud_query.get_single_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+pub fn show_mission_toast(
+    time: Res<Time>,
+    mut completed_events: EventReader<MissionCompleted>,
+    mut toast_query: Query<(&mut MissionToast, &mut Text, &mut Visibility)>,
+) {
+    let Ok((mut toast, mut text, mut visibility)) = toast_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(event) = completed_events.iter().last() {
+        text.sections[0].value = format!("Mission complete!\n{}\n+{} coins", event.description, event.reward);
+        *visibility = Visibility::Visible;
+        toast.timer.reset();
+    }
+
+    if toast.timer.tick(time.delta()).just_finished() {
+        *visibility = Visibility::Hidden;
+    }
+}
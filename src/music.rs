@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::prelude::AudioSource;
+use std::time::Duration;
+use crate::game::{GameSet, RunSetup};
+use crate::resources::{GameOver, GameSpeed, Paused, Settings};
+
+// Music volume is scaled by this while paused or on the game over screen
+const DUCKED_MUSIC_VOLUME: f64 = 0.3;
+const STEM_FADE: Duration = Duration::from_millis(600);
+// Stem volume changes smaller than this are not worth a new tween
+const VOLUME_EPSILON: f64 = 0.02;
+
+// One channel per music stem so each layer can be faded on its own
+#[derive(Resource)]
+pub struct MusicChannel;
+
+#[derive(Resource)]
+pub struct DriveStemChannel;
+
+#[derive(Resource)]
+pub struct PeakStemChannel;
+
+#[derive(Resource)]
+pub struct StingChannel;
+
+#[derive(Resource, Default)]
+pub struct MusicAssets {
+    pub base: Handle<AudioSource>,
+    pub drive: Handle<AudioSource>,
+    pub peak: Handle<AudioSource>,
+    pub game_over_sting: Handle<AudioSource>,
+}
+
+// Set when a run starts; the stems are started once all of them have loaded
+#[derive(Resource, Default)]
+pub struct MusicStartPending(pub bool);
+
+// How far the run is toward max speed: 0.0 at 1x, 1.0 at 4x
+pub fn music_intensity(multiplier: f32) -> f64 {
+    ((multiplier - 1.0) / 3.0).clamp(0.0, 1.0) as f64
+}
+
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Relative volume of the base, drive and peak stems at an intensity
+pub fn stem_weights(intensity: f64) -> [f64; 3] {
+    [
+        1.0 - 0.3 * smoothstep(0.6, 1.0, intensity),
+        smoothstep(0.15, 0.5, intensity),
+        smoothstep(0.55, 0.95, intensity),
+    ]
+}
+
+pub struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<MusicChannel>()
+            .add_audio_channel::<DriveStemChannel>()
+            .add_audio_channel::<PeakStemChannel>()
+            .add_audio_channel::<StingChannel>()
+            .init_resource::<MusicStartPending>()
+            .add_systems(Startup, load_music)
+            .add_systems(RunSetup, queue_music)
+            .add_systems(Update, (play_music, mix_music_stems, play_game_over_sting).in_set(GameSet::Ui));
+    }
+}
+
+pub fn load_music(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MusicAssets {
+        base: asset_server.load("audio/background_audio.ogg"),
+        drive: asset_server.load("audio/music_drive.wav"),
+        peak: asset_server.load("audio/music_peak.wav"),
+        game_over_sting: asset_server.load("audio/game_over_sting.wav"),
+    });
+}
+
+pub fn queue_music(mut pending: ResMut<MusicStartPending>) {
+    pending.0 = true;
+}
+
+// Every run starts all stems together from the top; only the base is audible at first.
+// The stems are the same length, so starting them on the same frame keeps the
+// layers in time as they loop.
+pub fn play_music(
+    music: Res<MusicAssets>,
+    sources: Res<Assets<AudioSource>>,
+    mut pending: ResMut<MusicStartPending>,
+    base_channel: Res<AudioChannel<MusicChannel>>,
+    drive_channel: Res<AudioChannel<DriveStemChannel>>,
+    peak_channel: Res<AudioChannel<PeakStemChannel>>,
+    sting_channel: Res<AudioChannel<StingChannel>>,
+) {
+    let stems = [&music.base, &music.drive, &music.peak];
+    if !pending.0 || !stems.iter().all(|stem| sources.contains(*stem)) {
+        return;
+    }
+    pending.0 = false;
+
+    sting_channel.stop();
+    base_channel.stop();
+    drive_channel.stop();
+    peak_channel.stop();
+
+    base_channel.play(music.base.clone()).looped();
+    drive_channel.play(music.drive.clone()).looped().with_volume(0.0);
+    peak_channel.play(music.peak.clone()).looped().with_volume(0.0);
+}
+
+// Fades the stems in as the speed multiplier rises and ducks them while paused or game over
+#[allow(clippy::too_many_arguments)]
+pub fn mix_music_stems(
+    settings: Res<Settings>,
+    game_over: Res<GameOver>,
+    paused: Res<Paused>,
+    game_speed: Res<GameSpeed>,
+    base_channel: Res<AudioChannel<MusicChannel>>,
+    drive_channel: Res<AudioChannel<DriveStemChannel>>,
+    peak_channel: Res<AudioChannel<PeakStemChannel>>,
+    sting_channel: Res<AudioChannel<StingChannel>>,
+    mut last_volumes: Local<Option<[f64; 3]>>,
+    mut last_sting_volume: Local<Option<f64>>,
+) {
+    let music_volume = settings.audio.music_volume();
+    let ducking = if game_over.0 || paused.0 { DUCKED_MUSIC_VOLUME } else { 1.0 };
+    let weights = stem_weights(music_intensity(game_speed.multiplier));
+    let volumes = weights.map(|weight| weight * music_volume * ducking);
+
+    let unchanged = last_volumes.is_some_and(|last| {
+        last.iter().zip(volumes).all(|(a, b)| (a - b).abs() < VOLUME_EPSILON)
+    });
+    if !unchanged {
+        base_channel.set_volume(volumes[0]).fade_in(AudioTween::linear(STEM_FADE));
+        drive_channel.set_volume(volumes[1]).fade_in(AudioTween::linear(STEM_FADE));
+        peak_channel.set_volume(volumes[2]).fade_in(AudioTween::linear(STEM_FADE));
+        *last_volumes = Some(volumes);
+    }
+
+    // The sting is never ducked, it plays over the game over screen
+    if *last_sting_volume != Some(music_volume) {
+        sting_channel.set_volume(music_volume);
+        *last_sting_volume = Some(music_volume);
+    }
+}
+
+pub fn play_game_over_sting(
+    game_over: Res<GameOver>,
+    music: Res<MusicAssets>,
+    sting_channel: Res<AudioChannel<StingChannel>>,
+) {
+    if game_over.is_changed() && game_over.0 {
+        sting_channel.play(music.game_over_sting.clone());
+    }
+}
@@ -0,0 +1,332 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::components::{OptionsText, OptionsUI};
+use crate::game::GameSet;
+use crate::persistence;
+use crate::resources::{DisplayMode, Paused, Settings, FPS_CAPS, RESOLUTIONS, SETTINGS_FILE};
+use crate::ui::UiClick;
+
+const VOLUME_STEP: f32 = 0.1;
+
+pub struct OptionsPlugin;
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OptionsMenu>()
+            .add_systems(Startup, spawn_options_ui)
+            .add_systems(
+                Update,
+                (
+                    toggle_options_menu,
+                    navigate_options,
+                    display_options_screen,
+                    apply_video_settings,
+                )
+                    .chain()
+                    .in_set(GameSet::Ui),
+            )
+            .add_systems(Last, limit_frame_rate);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum OptionRow {
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Mute,
+    Resolution,
+    DisplayMode,
+    VSync,
+    FpsCap,
+    ScreenShake,
+    ColorblindPalette,
+}
+
+impl OptionRow {
+    pub const ALL: [OptionRow; 10] = [
+        OptionRow::MasterVolume,
+        OptionRow::MusicVolume,
+        OptionRow::SfxVolume,
+        OptionRow::Mute,
+        OptionRow::Resolution,
+        OptionRow::DisplayMode,
+        OptionRow::VSync,
+        OptionRow::FpsCap,
+        OptionRow::ScreenShake,
+        OptionRow::ColorblindPalette,
+    ];
+
+    pub fn label(&self, settings: &Settings) -> String {
+        let audio = &settings.audio;
+        let video = &settings.video;
+        let gameplay = &settings.gameplay;
+        match self {
+            OptionRow::MasterVolume => format!("Master Volume  {}", volume_bar(audio.master)),
+            OptionRow::MusicVolume => format!("Music Volume   {}", volume_bar(audio.music)),
+            OptionRow::SfxVolume => format!("SFX Volume     {}", volume_bar(audio.sfx)),
+            OptionRow::Mute => format!("Mute           {}", on_off(audio.muted)),
+            OptionRow::Resolution => format!(
+                "Resolution     {}x{}",
+                video.resolution.0, video.resolution.1
+            ),
+            OptionRow::DisplayMode => format!("Display Mode   {}", video.display_mode.name()),
+            OptionRow::VSync => format!("VSync          {}", on_off(video.vsync)),
+            OptionRow::FpsCap => match video.fps_cap {
+                0 => "FPS Cap        Unlimited".to_string(),
+                cap => format!("FPS Cap        {}", cap),
+            },
+            OptionRow::ScreenShake => format!("Screen Shake   {}", on_off(gameplay.screen_shake)),
+            OptionRow::ColorblindPalette => {
+                format!("Colorblind     {}", on_off(gameplay.colorblind_palette))
+            }
+        }
+    }
+
+    // Applies a left (-1) or right (+1) press to the setting
+    pub fn adjust(&self, settings: &mut Settings, direction: f32) {
+        let step = |value: &mut f32| *value = (*value + direction * VOLUME_STEP).clamp(0.0, 1.0);
+        let audio = &mut settings.audio;
+        let video = &mut settings.video;
+        let gameplay = &mut settings.gameplay;
+        match self {
+            OptionRow::MasterVolume => step(&mut audio.master),
+            OptionRow::MusicVolume => step(&mut audio.music),
+            OptionRow::SfxVolume => step(&mut audio.sfx),
+            OptionRow::Mute => audio.muted = !audio.muted,
+            OptionRow::Resolution => video.resolution = cycle(&RESOLUTIONS, video.resolution, direction),
+            OptionRow::DisplayMode => {
+                video.display_mode = cycle(&DisplayMode::ALL, video.display_mode, direction)
+            }
+            OptionRow::VSync => video.vsync = !video.vsync,
+            OptionRow::FpsCap => video.fps_cap = cycle(&FPS_CAPS, video.fps_cap, direction),
+            OptionRow::ScreenShake => gameplay.screen_shake = !gameplay.screen_shake,
+            OptionRow::ColorblindPalette => {
+                gameplay.colorblind_palette = !gameplay.colorblind_palette
+            }
+        }
+    }
+}
+
+// Steps to the next or previous choice, wrapping around. Unknown values
+// (e.g. a hand-edited settings file) restart from the first choice.
+fn cycle<T: Copy + PartialEq>(choices: &[T], current: T, direction: f32) -> T {
+    let len = choices.len();
+    match choices.iter().position(|choice| *choice == current) {
+        Some(index) if direction < 0.0 => choices[(index + len - 1) % len],
+        Some(index) => choices[(index + 1) % len],
+        None => choices[0],
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "On" } else { "Off" }
+}
+
+fn volume_bar(value: f32) -> String {
+    let filled = (value * 10.0).round() as usize;
+    format!("[{}{}] {:>3}%", "#".repeat(filled), "-".repeat(10 - filled), (value * 100.0).round())
+}
+
+// Options overlay, opened from the pause screen
+#[derive(Resource, Default)]
+pub struct OptionsMenu {
+    pub open: bool,
+    pub selected: usize,
+}
+
+pub fn spawn_options_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.9)),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            OptionsUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Options",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            });
+
+            // One section per option row, filled in by display_options_screen
+            parent.spawn((
+                TextBundle {
+                    text: Text::default(),
+                    style: Style {
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                OptionsText,
+            ));
+
+            // Instructions
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "[Up/Down] Select  [Left/Right] Change  [O] Back",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+}
+
+pub fn toggle_options_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    paused: Res<Paused>,
+    mut menu: ResMut<OptionsMenu>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !paused.0 {
+        // Resuming the game always leaves the menu
+        if menu.open {
+            menu.open = false;
+        }
+    } else if keyboard_input.just_pressed(KeyCode::O) {
+        menu.open = !menu.open;
+        click_events.send(UiClick);
+    }
+}
+
+pub fn navigate_options(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut menu: ResMut<OptionsMenu>,
+    mut settings: ResMut<Settings>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !menu.open {
+        return;
+    }
+
+    let row_count = OptionRow::ALL.len();
+    if keyboard_input.any_just_pressed([KeyCode::Up, KeyCode::W]) {
+        menu.selected = (menu.selected + row_count - 1) % row_count;
+        click_events.send(UiClick);
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Down, KeyCode::S]) {
+        menu.selected = (menu.selected + 1) % row_count;
+        click_events.send(UiClick);
+    }
+
+    let mut direction = 0.0;
+    if keyboard_input.any_just_pressed([KeyCode::Left, KeyCode::A]) {
+        direction -= 1.0;
+    }
+    if keyboard_input.any_just_pressed([KeyCode::Right, KeyCode::D, KeyCode::Return]) {
+        direction += 1.0;
+    }
+
+    if direction != 0.0 {
+        OptionRow::ALL[menu.selected].adjust(&mut settings, direction);
+        persistence::save(SETTINGS_FILE, &*settings);
+        click_events.send(UiClick);
+    }
+}
+
+pub fn display_options_screen(
+    menu: Res<OptionsMenu>,
+    settings: Res<Settings>,
+    mut ui_query: Query<&mut Visibility, With<OptionsUI>>,
+    mut text_query: Query<&mut Text, With<OptionsText>>,
+) {
+    if !menu.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    if let Ok(mut visibility) = ui_query.get_single_mut() {
+        *visibility = if menu.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let palette = settings.gameplay.palette();
+        text.sections = OptionRow::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let selected = i == menu.selected;
+                TextSection::new(
+                    format!("{} {}\n", if selected { ">" } else { " " }, row.label(&settings)),
+                    TextStyle {
+                        font_size: 28.0,
+                        color: if selected { palette.highlight } else { Color::WHITE },
+                        ..default()
+                    },
+                )
+            })
+            .collect();
+    }
+}
+
+// Applies window settings live. The window starts with them already applied in main.
+pub fn apply_video_settings(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let video = &settings.video;
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+
+    let (width, height) = video.resolution;
+    if window.resolution.width() != width || window.resolution.height() != height {
+        window.resolution.set(width, height);
+    }
+    if window.mode != video.window_mode() {
+        window.mode = video.window_mode();
+    }
+    if window.present_mode != video.present_mode() {
+        window.present_mode = video.present_mode();
+    }
+}
+
+// With an FPS cap set, sleeps until the frame has taken its share of a second
+// since it started. The event loop itself keeps running continuously.
+pub fn limit_frame_rate(settings: Res<Settings>, time: Res<Time>) {
+    let (Some(frame_time), Some(frame_start)) = (settings.video.frame_time(), time.last_update()) else {
+        return;
+    };
+    let elapsed = frame_start.elapsed();
+    if elapsed < frame_time {
+        std::thread::sleep(frame_time - elapsed);
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+// Save files live next to the game in a "saves" folder
+const SAVE_DIR: &str = "saves";
+
+pub fn save_path(file_name: &str) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(file_name)
+}
+
+// Returns None if the file is missing or can't be parsed
+pub fn load<T: DeserializeOwned>(file_name: &str) -> Option<T> {
+    let contents = fs::read_to_string(save_path(file_name)).ok()?;
+    match ron::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("Failed to parse {}: {:?}", file_name, e);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(file_name: &str, value: &T) {
+    let result = match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => write(file_name, &contents).map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Failed to save {}: {}", file_name, e);
+    }
+}
+
+// Writes text as is, for exports in other formats
+pub fn write(file_name: &str, contents: &str) -> io::Result<()> {
+    fs::create_dir_all(SAVE_DIR)?;
+    fs::write(save_path(file_name), contents)
+}
@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+// Logical size of the playfield in world units. The camera scales this to fit
+// any window, so gameplay never depends on the window size.
+pub const PLAYFIELD_SIZE: Vec2 = Vec2::new(800.0, 600.0);
+pub const HALF_WIDTH: f32 = PLAYFIELD_SIZE.x / 2.0;
+pub const HALF_HEIGHT: f32 = PLAYFIELD_SIZE.y / 2.0;
+
+// How far past the top and bottom edges things spawn, wrap and despawn
+pub const OFFSCREEN_MARGIN: f32 = 50.0;
+
+// Enemies spawn and road lines wrap to just above the top edge
+pub const SPAWN_Y: f32 = HALF_HEIGHT + OFFSCREEN_MARGIN;
+// Enemies despawn and road lines wrap once just below the bottom edge
+pub const DESPAWN_Y: f32 = -HALF_HEIGHT - OFFSCREEN_MARGIN;
+
+// Half width of the road texture, shoulders and edge lines included
+pub const ROAD_HALF_WIDTH: f32 = 300.0;
+// Half width of the lanes, inside the edge lines painted on road.png
+pub const LANES_HALF_WIDTH: f32 = 245.0;
+// Gravel strip between the road and the grass verge
+pub const SHOULDER_WIDTH: f32 = 30.0;
+
+// Shoulder and edge line painted on the road texture outside the lanes. The
+// player can drive on it, traffic stays off it.
+pub const ROAD_MARGIN: f32 = ROAD_HALF_WIDTH - LANES_HALF_WIDTH;
+
+// Scale for showing distances: the road scrolls 150 units, 50m, a second at 1x
+pub const UNITS_PER_METER: f32 = 3.0;
@@ -1,32 +1,1073 @@
 use bevy::prelude::*;
+use bevy::window::{PresentMode, WindowMode};
+use rand::seq::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use crate::components::{CarStats, CarType, Hazard, PowerUp, SceneryKind};
 
 #[derive(Resource)]
 pub struct EnemySpawnTimer(pub Timer);
 
+impl EnemySpawnTimer {
+    pub fn new(interval: f32) -> Self {
+        Self(Timer::from_seconds(interval, TimerMode::Repeating))
+    }
+}
+
 impl Default for EnemySpawnTimer {
     fn default() -> Self {
-        Self(Timer::from_seconds(1.0, TimerMode::Repeating))
+        Self::new(1.0)
     }
 }
 
 #[derive(Resource)]
 pub struct GameSpeed {
     pub time_elapsed: f32,
+    // The speed ramp alone, before nitro and slow-mo
+    pub ramp: f32,
     pub multiplier: f32,
+    // World units the road has scrolled this run
+    pub distance: f32,
 }
 
 impl Default for GameSpeed {
     fn default() -> Self {
         Self {
             time_elapsed: 0.0,
+            ramp: 1.0,
             multiplier: 1.0,
+            distance: 0.0,
+        }
+    }
+}
+
+// Points for the current run: one per meter, more while nitro is on or when
+// driving against the traffic
+#[derive(Resource, Default)]
+pub struct Score(pub f32);
+
+// Seconds the player has driven in oncoming lanes this run
+#[derive(Resource, Default)]
+pub struct OncomingTime(pub f32);
+
+// Speed multiplier factors while nitro or slow-mo is active
+const NITRO_SPEED: f32 = 1.5;
+const SLOW_MO_SPEED: f32 = 0.5;
+const NITRO_SCORE: f32 = 2.0;
+
+// Power-up effects in play and the seconds each has left
+#[derive(Resource, Default)]
+pub struct ActiveEffects {
+    remaining: HashMap<PowerUp, f32>,
+}
+
+impl ActiveEffects {
+    // Starts an effect, or restarts its timer if it is already active
+    pub fn activate(&mut self, kind: PowerUp) {
+        self.remaining.insert(kind, kind.duration());
+    }
+
+    pub fn end(&mut self, kind: PowerUp) {
+        self.remaining.remove(&kind);
+    }
+
+    pub fn is_active(&self, kind: PowerUp) -> bool {
+        self.remaining.contains_key(&kind)
+    }
+
+    pub fn remaining(&self, kind: PowerUp) -> Option<f32> {
+        self.remaining.get(&kind).copied()
+    }
+
+    pub fn tick(&mut self, seconds: f32) {
+        for remaining in self.remaining.values_mut() {
+            *remaining -= seconds;
         }
+        self.remaining.retain(|_, remaining| *remaining > 0.0);
+    }
+
+    pub fn clear(&mut self) {
+        self.remaining.clear();
+    }
+
+    // Applied on top of the speed ramp
+    pub fn speed_factor(&self) -> f32 {
+        let mut factor = 1.0;
+        if self.is_active(PowerUp::Nitro) {
+            factor *= NITRO_SPEED;
+        }
+        if self.is_active(PowerUp::SlowMo) {
+            factor *= SLOW_MO_SPEED;
+        }
+        factor
+    }
+
+    pub fn score_factor(&self) -> f32 {
+        if self.is_active(PowerUp::Nitro) { NITRO_SCORE } else { 1.0 }
+    }
+}
+
+pub const MAX_WANTED_LEVEL: usize = 3;
+
+// Attention the player's reckless driving has drawn. Each whole point of heat
+// puts another police car on their tail.
+#[derive(Resource, Default)]
+pub struct Heat {
+    pub value: f32,
+    // Seconds the player has kept every police car at a distance
+    pub escape_time: f32,
+    // Seconds until another police car may join the chase
+    pub spawn_cooldown: f32,
+}
+
+impl Heat {
+    pub fn wanted_level(&self) -> usize {
+        (self.value as usize).min(MAX_WANTED_LEVEL)
+    }
+}
+
+// Randomness that shapes the run: road layout, traffic, pickups, hazards and
+// police. Seeded from the OS, or from a fixed seed for reproducible simulations.
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_os_rng())
+    }
+}
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
     }
 }
 
 #[derive(Resource, Default)]
 pub struct GameOver(pub bool);
 
-pub fn game_not_over(game_over: Res<GameOver>) -> bool {
-    !game_over.0
+#[derive(Resource, Default)]
+pub struct Paused(pub bool);
+
+// The main menu is up
+#[derive(Resource, Default)]
+pub struct InMenu(pub bool);
+
+// Gameplay only advances while the run is neither over nor paused, and no
+// mode is being picked
+pub fn game_running(game_over: Res<GameOver>, paused: Res<Paused>, in_menu: Res<InMenu>) -> bool {
+    !game_over.0 && !paused.0 && !in_menu.0
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum GameMode {
+    // Survive for as long as possible
+    #[default]
+    Endless,
+    // Reach the finish of a fixed course before the clock runs out
+    TimeTrial,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Endless, GameMode::TimeTrial];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Endless => "Endless",
+            GameMode::TimeTrial => "Time Trial",
+        }
+    }
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Debug, Default)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    // Seconds between enemy spawns
+    pub fn spawn_interval(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.3,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.7,
+        }
+    }
+
+    // Seconds for the speed multiplier to grow by 1x
+    pub fn speed_ramp_time(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 45.0,
+            Difficulty::Normal => 30.0,
+            Difficulty::Hard => 20.0,
+        }
+    }
+}
+
+// Steering intent for the player car, filled by the keyboard or a bot
+#[derive(Resource, Default)]
+pub struct PlayerInput {
+    pub steer: f32,
+    pub accelerate: bool,
+}
+
+// Texture handles used by gameplay systems. Left as default handles when
+// running headless, where nothing is rendered.
+#[derive(Resource, Default)]
+pub struct GameAssets {
+    pub cars: HashMap<&'static str, Handle<Image>>,
+    pub scenery: HashMap<&'static str, Handle<Image>>,
+    pub power_ups: HashMap<&'static str, Handle<Image>>,
+    pub hazards: HashMap<&'static str, Handle<Image>>,
+    pub roads: HashMap<String, Handle<Image>>,
+    pub road_line: Handle<Image>,
+    pub lane_ends_sign: Handle<Image>,
+    pub coin: Handle<Image>,
+    pub explosion: Handle<Image>,
+}
+
+impl GameAssets {
+    pub fn car(&self, car_type: CarType) -> Handle<Image> {
+        self.cars.get(car_type.asset_path()).cloned().unwrap_or_default()
+    }
+
+    pub fn scenery(&self, kind: SceneryKind) -> Handle<Image> {
+        self.scenery.get(kind.asset_path()).cloned().unwrap_or_default()
+    }
+
+    pub fn power_up(&self, kind: PowerUp) -> Handle<Image> {
+        self.power_ups.get(kind.asset_path()).cloned().unwrap_or_default()
+    }
+
+    pub fn hazard(&self, kind: Hazard) -> Handle<Image> {
+        self.hazards.get(kind.asset_path()).cloned().unwrap_or_default()
+    }
+
+    pub fn road(&self, path: &str) -> Handle<Image> {
+        self.roads.get(path).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum TimeOfDay {
+    Day,
+    Dusk,
+    Night,
+}
+
+impl TimeOfDay {
+    // 1.0 in full daylight, 0.0 at night
+    pub fn daylight(&self) -> f32 {
+        match self {
+            TimeOfDay::Day => 1.0,
+            TimeOfDay::Dusk => 0.55,
+            TimeOfDay::Night => 0.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Weather {
+    Clear,
+    Rain,
+    Fog,
+    Snow,
+}
+
+impl Weather {
+    // Share of the tyres' dry grip left in this weather
+    pub fn grip(&self) -> f32 {
+        match self {
+            Weather::Clear | Weather::Fog => 1.0,
+            Weather::Rain => 0.6,
+            Weather::Snow => 0.4,
+        }
+    }
+}
+
+// Light and weather right now. Both ease toward the current stage's so a new
+// stage does not switch them abruptly.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct Atmosphere {
+    pub daylight: f32,
+    pub weather: Weather,
+    // How strong the weather is, from 0.0 (none) to 1.0
+    pub intensity: f32,
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self {
+            daylight: 1.0,
+            weather: Weather::Clear,
+            intensity: 0.0,
+        }
+    }
+}
+
+impl Atmosphere {
+    pub fn grip(&self) -> f32 {
+        1.0 - (1.0 - self.weather.grip()) * self.intensity
+    }
+}
+
+// One stretch of road, see assets/stages.ron
+#[derive(Deserialize, Clone)]
+pub struct StageDef {
+    pub name: String,
+    // Meters from the start of the run where this stage begins
+    pub distance: f32,
+    pub road_texture: String,
+    // Whether the road texture has the center line painted on
+    pub center_line: bool,
+    pub lanes: usize,
+    // Lanes on the left that carry oncoming traffic
+    #[serde(default)]
+    pub oncoming_lanes: usize,
+    // Largest sideways offset of the road's curves
+    pub curve: f32,
+    // Whether lanes can close for a while
+    pub merges: bool,
+    pub verge_color: (f32, f32, f32),
+    pub shoulder_color: (f32, f32, f32),
+    pub tint: (f32, f32, f32),
+    pub guardrail: bool,
+    pub time_of_day: TimeOfDay,
+    pub weather: Weather,
+    // Weighted choices for the verge scenery and the traffic
+    pub scenery: Vec<(SceneryKind, u32)>,
+    pub traffic: Vec<(CarType, u32)>,
+}
+
+// Stage definitions, embedded so headless runs and tests see the same stages
+#[derive(Resource)]
+pub struct Stages(pub Vec<StageDef>);
+
+impl Default for Stages {
+    fn default() -> Self {
+        match Stages::parse(include_str!("../assets/stages.ron")) {
+            Ok(stages) => stages,
+            Err(e) => {
+                eprintln!("Failed to load assets/stages.ron: {}", e);
+                Self(vec![StageDef::default()])
+            }
+        }
+    }
+}
+
+// A plain city road, used when assets/stages.ron cannot be loaded
+impl Default for StageDef {
+    fn default() -> Self {
+        Self {
+            name: "City".to_string(),
+            distance: 0.0,
+            road_texture: "road.png".to_string(),
+            center_line: true,
+            lanes: 4,
+            oncoming_lanes: 2,
+            curve: 40.0,
+            merges: false,
+            verge_color: (0.32, 0.32, 0.34),
+            shoulder_color: (0.55, 0.55, 0.55),
+            tint: (1.0, 1.0, 1.0),
+            guardrail: false,
+            time_of_day: TimeOfDay::Day,
+            weather: Weather::Clear,
+            scenery: vec![(SceneryKind::Building, 1)],
+            traffic: vec![(CarType::TealSedan, 1)],
+        }
+    }
+}
+
+impl Stages {
+    // Reads stage definitions and checks they can be driven
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let stages: Vec<StageDef> = ron::from_str(source).map_err(|e| e.to_string())?;
+        if stages.is_empty() {
+            return Err("no stages defined".to_string());
+        }
+        for stage in &stages {
+            if stage.lanes == 0 {
+                return Err(format!("stage {} needs at least one lane", stage.name));
+            }
+            if stage.oncoming_lanes >= stage.lanes {
+                return Err(format!("stage {} needs a lane in the player's direction", stage.name));
+            }
+            if stage.center_line && (stage.lanes % 2 != 0 || stage.merges) {
+                return Err(format!(
+                    "stage {} has a painted center line, so it needs an even lane count and no merges",
+                    stage.name
+                ));
+            }
+        }
+        Ok(Self(stages))
+    }
+
+    pub fn get(&self, index: usize) -> &StageDef {
+        &self.0[index.min(self.0.len() - 1)]
+    }
+
+    // Index of the stage the run is in after `meters`
+    pub fn index_at(&self, meters: f32) -> usize {
+        self.0.iter().rposition(|stage| meters >= stage.distance).unwrap_or(0)
+    }
+}
+
+// Index into Stages of the stage the run is in
+#[derive(Resource, Default)]
+pub struct CurrentStage(pub usize);
+
+// Coins picked up on the road this run
+#[derive(Resource, Default)]
+pub struct RunCoins(pub u32);
+
+// Distance driven that earns one coin at the end of a run
+const METERS_PER_COIN: f32 = 50.0;
+
+impl RunCoins {
+    // What the run pays into the wallet after `meters`
+    pub fn earned(&self, meters: f32) -> u32 {
+        self.0 + (meters / METERS_PER_COIN) as u32
+    }
+}
+
+pub const PROFILE_FILE: &str = "profile.ron";
+
+// Coins for each upgrade tier, so also the number of tiers
+pub const UPGRADE_PRICES: [u32; 3] = [100, 250, 500];
+
+// Stat bonuses per tier
+const HANDLING_PER_TIER: f32 = 0.15;
+const ACCELERATION_PER_TIER: f32 = 0.2;
+const TOP_SPEED_PER_TIER: f32 = 40.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Upgrade {
+    Handling,
+    Acceleration,
+    TopSpeed,
+    Armor,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 4] = [Upgrade::Handling, Upgrade::Acceleration, Upgrade::TopSpeed, Upgrade::Armor];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Upgrade::Handling => "Handling",
+            Upgrade::Acceleration => "Acceleration",
+            Upgrade::TopSpeed => "Top Speed",
+            Upgrade::Armor => "Armor",
+        }
+    }
+}
+
+// Upgrade tiers bought for one car
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpgradeTiers {
+    pub handling: usize,
+    pub acceleration: usize,
+    pub top_speed: usize,
+    // Each tier is one extra hit point
+    pub armor: usize,
+}
+
+impl UpgradeTiers {
+    pub fn tier(&self, upgrade: Upgrade) -> usize {
+        match upgrade {
+            Upgrade::Handling => self.handling,
+            Upgrade::Acceleration => self.acceleration,
+            Upgrade::TopSpeed => self.top_speed,
+            Upgrade::Armor => self.armor,
+        }
+    }
+
+    fn tier_mut(&mut self, upgrade: Upgrade) -> &mut usize {
+        match upgrade {
+            Upgrade::Handling => &mut self.handling,
+            Upgrade::Acceleration => &mut self.acceleration,
+            Upgrade::TopSpeed => &mut self.top_speed,
+            Upgrade::Armor => &mut self.armor,
+        }
+    }
+
+    // Price of the next tier, or None once the upgrade is maxed out
+    pub fn next_price(&self, upgrade: Upgrade) -> Option<u32> {
+        UPGRADE_PRICES.get(self.tier(upgrade)).copied()
+    }
+
+    // A car's stock stats with these upgrades fitted
+    pub fn apply(&self, stats: CarStats) -> CarStats {
+        CarStats {
+            handling: stats.handling * (1.0 + HANDLING_PER_TIER * self.handling as f32),
+            acceleration: stats.acceleration * (1.0 + ACCELERATION_PER_TIER * self.acceleration as f32),
+            top_speed: stats.top_speed + TOP_SPEED_PER_TIER * self.top_speed as f32,
+        }
+    }
+}
+
+// The player's wallet and garage, saved whenever either changes
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub coins: u32,
+    pub owned_cars: Vec<CarType>,
+    pub selected_car: CarType,
+    pub upgrades: HashMap<CarType, UpgradeTiers>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            coins: 0,
+            owned_cars: vec![CarType::Player],
+            selected_car: CarType::Player,
+            upgrades: HashMap::new(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn owns(&self, car_type: CarType) -> bool {
+        self.owned_cars.contains(&car_type)
+    }
+
+    // Buys the car if the wallet allows it. Returns whether it was bought.
+    pub fn buy(&mut self, car_type: CarType) -> bool {
+        if self.owns(car_type) || self.coins < car_type.price() {
+            return false;
+        }
+        self.coins -= car_type.price();
+        self.owned_cars.push(car_type);
+        true
+    }
+
+    pub fn upgrades(&self, car_type: CarType) -> UpgradeTiers {
+        self.upgrades.get(&car_type).copied().unwrap_or_default()
+    }
+
+    // Buys the next tier of an upgrade for an owned car. Returns whether it was bought.
+    pub fn buy_upgrade(&mut self, car_type: CarType, upgrade: Upgrade) -> bool {
+        let Some(price) = self.upgrades(car_type).next_price(upgrade) else {
+            return false;
+        };
+        if !self.owns(car_type) || self.coins < price {
+            return false;
+        }
+        self.coins -= price;
+        *self.upgrades.entry(car_type).or_default().tier_mut(upgrade) += 1;
+        true
+    }
+}
+
+pub const MISSIONS_FILE: &str = "missions.ron";
+pub const ACTIVE_MISSIONS: usize = 3;
+
+// What a mission asks for. Counts add up over any number of runs; distances and
+// times have to be reached within one run.
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum MissionKind {
+    NearMisses { count: u32 },
+    CleanDistance { meters: u32 },
+    CollectPowerUps { kind: PowerUp, count: u32 },
+    CollectCoins { count: u32 },
+    OncomingTime { seconds: u32 },
+    Survive { seconds: u32 },
+}
+
+impl MissionKind {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        match rng.random_range(0..6) {
+            0 => MissionKind::NearMisses {
+                count: *[5, 10, 20].choose(rng).unwrap(),
+            },
+            1 => MissionKind::CleanDistance {
+                meters: *[1000, 2500, 5000].choose(rng).unwrap(),
+            },
+            2 => MissionKind::CollectPowerUps {
+                kind: *PowerUp::ALL.choose(rng).unwrap(),
+                count: *[2, 3, 5].choose(rng).unwrap(),
+            },
+            3 => MissionKind::CollectCoins {
+                count: *[25, 50, 100].choose(rng).unwrap(),
+            },
+            4 => MissionKind::OncomingTime {
+                seconds: *[10, 20, 40].choose(rng).unwrap(),
+            },
+            _ => MissionKind::Survive {
+                seconds: *[60, 90, 120].choose(rng).unwrap(),
+            },
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            MissionKind::NearMisses { count } => format!("Near-miss {} cars", count),
+            MissionKind::CleanDistance { meters } => {
+                format!("Drive {:.1} km without a scratch", *meters as f32 / 1000.0)
+            }
+            MissionKind::CollectPowerUps { kind, count } => format!("Collect {} {} power-ups", count, kind.label()),
+            MissionKind::CollectCoins { count } => format!("Collect {} coins", count),
+            MissionKind::OncomingTime { seconds } => format!("Drive {}s against the traffic", seconds),
+            MissionKind::Survive { seconds } => format!("Survive {}s in one run", seconds),
+        }
+    }
+
+    pub fn target(&self) -> f32 {
+        match *self {
+            MissionKind::NearMisses { count } => count as f32,
+            MissionKind::CleanDistance { meters } => meters as f32,
+            MissionKind::CollectPowerUps { count, .. } => count as f32,
+            MissionKind::CollectCoins { count } => count as f32,
+            MissionKind::OncomingTime { seconds } => seconds as f32,
+            MissionKind::Survive { seconds } => seconds as f32,
+        }
+    }
+
+    // Whether progress starts over with every run
+    pub fn per_run(&self) -> bool {
+        matches!(
+            self,
+            MissionKind::CleanDistance { .. } | MissionKind::OncomingTime { .. } | MissionKind::Survive { .. }
+        )
+    }
+
+    // Coins paid on completion
+    pub fn reward(&self) -> u32 {
+        match *self {
+            MissionKind::NearMisses { count } => count * 10,
+            MissionKind::CleanDistance { meters } => meters / 20,
+            MissionKind::CollectPowerUps { count, .. } => count * 30,
+            MissionKind::CollectCoins { count } => count,
+            MissionKind::OncomingTime { seconds } => seconds * 5,
+            MissionKind::Survive { seconds } => seconds * 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Mission {
+    pub kind: MissionKind,
+    pub progress: f32,
+}
+
+impl Mission {
+    pub fn new(kind: MissionKind) -> Self {
+        Self { kind, progress: 0.0 }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.kind.target()
+    }
+}
+
+// The active missions, saved as they progress
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct Missions {
+    pub active: Vec<Mission>,
+}
+
+impl Default for Missions {
+    fn default() -> Self {
+        let mut missions = Self { active: Vec::new() };
+        missions.fill(&mut rand::rng());
+        missions
+    }
+}
+
+impl Missions {
+    // Tops the list up to ACTIVE_MISSIONS, never with two of the same kind
+    pub fn fill(&mut self, rng: &mut impl Rng) {
+        while self.active.len() < ACTIVE_MISSIONS {
+            let kind = MissionKind::random(rng);
+            let duplicate = self
+                .active
+                .iter()
+                .any(|mission| std::mem::discriminant(&mission.kind) == std::mem::discriminant(&kind));
+            if !duplicate {
+                self.active.push(Mission::new(kind));
+            }
+        }
+    }
+}
+
+pub const STATS_FILE: &str = "stats.ron";
+pub const ACHIEVEMENTS_FILE: &str = "achievements.ron";
+
+pub const STATS_CSV_FILE: &str = "stats.csv";
+pub const STATS_JSON_FILE: &str = "stats.json";
+
+// Totals over every run, saved when a run ends
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    // Endless runs only
+    pub runs: u32,
+    // Meters
+    pub distance: f32,
+    // Seconds survived in endless runs
+    pub time: f32,
+    // Crashes by the car hit
+    pub crashes: HashMap<CarType, u32>,
+    // Crashes into roadworks and potholes
+    pub barrier_crashes: u32,
+    pub best_speed: f32,
+    pub near_misses: u32,
+}
+
+impl LifetimeStats {
+    pub fn average_survival(&self) -> f32 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.time / self.runs as f32
+        }
+    }
+
+    // Crashes in CarType::ALL order, skipping cars never hit
+    pub fn crashes_by_car(&self) -> Vec<(CarType, u32)> {
+        CarType::ALL
+            .iter()
+            .filter_map(|car_type| self.crashes.get(car_type).map(|count| (*car_type, *count)))
+            .collect()
+    }
+
+    // One "stat,value" row per figure
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![
+            "stat,value".to_string(),
+            format!("runs,{}", self.runs),
+            format!("distance_m,{:.0}", self.distance),
+            format!("time_s,{:.1}", self.time),
+            format!("average_survival_s,{:.1}", self.average_survival()),
+            format!("best_speed,{:.0}", self.best_speed),
+            format!("near_misses,{}", self.near_misses),
+            format!("crashes_barrier,{}", self.barrier_crashes),
+        ];
+        for (car_type, count) in self.crashes_by_car() {
+            rows.push(format!("crashes_{:?},{}", car_type, count));
+        }
+        rows.join("\n") + "\n"
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Achievement {
+    FullThrottle,
+    Wreck,
+    RoadTrip,
+    Daredevil,
+    WrongWay,
+    MostWanted,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 6] = [
+        Achievement::FullThrottle,
+        Achievement::Wreck,
+        Achievement::RoadTrip,
+        Achievement::Daredevil,
+        Achievement::WrongWay,
+        Achievement::MostWanted,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::FullThrottle => "Full Throttle",
+            Achievement::Wreck => "Wreck",
+            Achievement::RoadTrip => "Road Trip",
+            Achievement::Daredevil => "Daredevil",
+            Achievement::WrongWay => "Wrong Way",
+            Achievement::MostWanted => "Most Wanted",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FullThrottle => "Survive 90s at the 4x speed multiplier",
+            Achievement::Wreck => "Crash within 3 seconds of the start",
+            Achievement::RoadTrip => "Drive 10 km over all your runs",
+            Achievement::Daredevil => "Near-miss 20 cars in one run",
+            Achievement::WrongWay => "Drive 30s against the traffic in one run",
+            Achievement::MostWanted => "Reach the highest wanted level",
+        }
+    }
+}
+
+// Achievements unlocked so far
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Achievements {
+    pub unlocked: HashSet<Achievement>,
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, achievement: Achievement) -> bool {
+        self.unlocked.contains(&achievement)
+    }
+
+    // Returns false if it was already unlocked
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        self.unlocked.insert(achievement)
+    }
+}
+
+pub const TRIAL_RECORD_FILE: &str = "time_trial.ron";
+
+// Split times of the fastest finished time trial, one per checkpoint with the
+// finish last
+#[derive(Resource, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrialRecord {
+    pub best_splits: Vec<f32>,
+}
+
+impl TrialRecord {
+    pub fn best_time(&self) -> Option<f32> {
+        self.best_splits.last().copied()
+    }
+
+    pub fn best_split(&self, index: usize) -> Option<f32> {
+        self.best_splits.get(index).copied()
+    }
+
+    // Keeps the splits if they make a faster finish. Returns whether they did.
+    pub fn submit(&mut self, splits: &[f32]) -> bool {
+        let Some(&time) = splits.last() else {
+            return false;
+        };
+        if self.best_time().is_none_or(|best| time < best) {
+            self.best_splits = splits.to_vec();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub const HIGH_SCORE_FILE: &str = "highscore.ron";
+pub const GHOST_FILE: &str = "ghost.ron";
+
+// How often the player's position is sampled for the ghost
+pub const GHOST_SAMPLE_INTERVAL: f32 = 0.05;
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct HighScore {
+    pub best_time: f32,
+}
+
+// Player positions sampled every GHOST_SAMPLE_INTERVAL seconds of a run
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct GhostRun {
+    pub positions: Vec<[f32; 2]>,
+}
+
+impl GhostRun {
+    // Position at a point in the run, interpolated between samples
+    pub fn position_at(&self, time: f32) -> Option<Vec2> {
+        let index = (time / GHOST_SAMPLE_INTERVAL) as usize;
+        let current = self.positions.get(index)?;
+        let next = self.positions.get(index + 1).unwrap_or(current);
+        let t = (time / GHOST_SAMPLE_INTERVAL).fract();
+        Some(Vec2::from(*current).lerp(Vec2::from(*next), t))
+    }
+}
+
+// Ghost of the personal best run and the recording of the current run
+#[derive(Resource, Default)]
+pub struct GhostData {
+    pub best: Option<GhostRun>,
+    pub current: GhostRun,
+}
+
+pub const SETTINGS_FILE: &str = "settings.ron";
+
+// Player preferences, saved whenever they change in the Options menu
+#[derive(Resource, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    pub video: VideoSettings,
+    pub gameplay: GameplaySettings,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.8,
+            sfx: 0.8,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn music_volume(&self) -> f64 {
+        if self.muted { 0.0 } else { (self.master * self.music) as f64 }
+    }
+
+    pub fn sfx_volume(&self) -> f64 {
+        if self.muted { 0.0 } else { (self.master * self.sfx) as f64 }
+    }
+}
+
+pub const RESOLUTIONS: [(f32, f32); 6] = [
+    (800.0, 600.0),
+    (1024.0, 768.0),
+    (1280.0, 720.0),
+    (1280.0, 960.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+];
+
+// 0 means no cap
+pub const FPS_CAPS: [u32; 5] = [0, 30, 60, 120, 144];
+
+#[derive(Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub const ALL: [DisplayMode; 3] = [DisplayMode::Windowed, DisplayMode::Borderless, DisplayMode::Fullscreen];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Fullscreen => "Fullscreen",
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VideoSettings {
+    pub resolution: (f32, f32),
+    pub display_mode: DisplayMode,
+    pub vsync: bool,
+    pub fps_cap: u32,
+}
+
+impl VideoSettings {
+    pub fn window_mode(&self) -> WindowMode {
+        match self.display_mode {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    // Shortest time a frame may take, or None when the frame rate is uncapped
+    pub fn frame_time(&self) -> Option<Duration> {
+        match self.fps_cap {
+            0 => None,
+            cap => Some(Duration::from_secs_f64(1.0 / cap as f64)),
+        }
+    }
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        Self {
+            resolution: RESOLUTIONS[0],
+            display_mode: DisplayMode::Windowed,
+            vsync: true,
+            fps_cap: 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplaySettings {
+    pub screen_shake: bool,
+    pub colorblind_palette: bool,
+}
+
+impl Default for GameplaySettings {
+    fn default() -> Self {
+        Self {
+            screen_shake: true,
+            colorblind_palette: false,
+        }
+    }
+}
+
+impl GameplaySettings {
+    pub fn palette(&self) -> Palette {
+        if self.colorblind_palette {
+            Palette::COLORBLIND
+        } else {
+            Palette::STANDARD
+        }
+    }
+}
+
+// Colors that carry meaning, swapped for the Okabe-Ito palette in colorblind mode
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub danger: Color,
+    pub highlight: Color,
+    pub explosion: Color,
+    // Sprite tints for hazards and power-ups on the road
+    pub hazard: Color,
+    pub power_up: Color,
+}
+
+impl Palette {
+    pub const STANDARD: Palette = Palette {
+        danger: Color::RED,
+        highlight: Color::YELLOW,
+        explosion: Color::WHITE,
+        hazard: Color::WHITE,
+        power_up: Color::WHITE,
+    };
+
+    pub const COLORBLIND: Palette = Palette {
+        danger: Color::rgb(0.90, 0.62, 0.0),
+        highlight: Color::rgb(0.34, 0.71, 0.91),
+        explosion: Color::rgb(0.90, 0.62, 0.0),
+        hazard: Color::rgb(0.90, 0.62, 0.0),
+        power_up: Color::rgb(0.0, 0.62, 0.45),
+    };
 }
@@ -1,112 +1,75 @@
 use bevy::prelude::*;
 use bevy::asset::AssetServer;
 use crate::components::*;
-use crate::ui::spawn_game_over_ui;
-use bevy_kira_audio::{Audio, AudioControl};
+use crate::resources::{GameAssets, Stages};
 
 use bevy::window::PrimaryWindow;
-use windows::core::PCWSTR;
-
-#[cfg(target_os = "windows")]
 use bevy::winit::WinitWindows;
-#[cfg(target_os = "windows")]
-
-
+use winit::window::Icon;
 
+const WINDOW_ICON: &[u8] = include_bytes!("../assets/icon.ico");
 
-pub fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Camera2dBundle::default());
-
-    // Road background
-    commands.spawn((
-        SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgb(0.2, 0.2, 0.2),
-                custom_size: Some(Vec2::new(800.0, 600.0)),
-                ..default()
-            },
-            ..default()
-        },
-        Road,
-    ));
-
-    let line_texture = asset_server.load("white_line.png");
-    for x in [-200.0, 0.0, 200.0] {
-        super::systems::road::spawn_road_lines(&mut commands, line_texture.clone(), x, 10);
+pub fn load_game_assets(
+    mut assets: ResMut<GameAssets>,
+    asset_server: Res<AssetServer>,
+    stages: Res<Stages>,
+) {
+    for car_type in CarType::ALL {
+        assets.cars.insert(car_type.asset_path(), asset_server.load(car_type.asset_path()));
     }
-
-    // Player with collision bounds
-    let player_car_type = CarType::Player;
-    let player_collision_bounds = player_car_type.collision_bounds();
-
-    commands.spawn((
-        SpriteBundle {
-            texture: asset_server.load(player_car_type.asset_path()),
-            transform: Transform {
-                translation: Vec3::new(0.0, -200.0, 10.0),
-                scale: Vec3::splat(0.1),
-                ..default()
-            },
-            ..default()
-        },
-        Player,
-        Velocity { speed: 150.0 },
-        player_car_type,
-        CollisionBounds {
-            size: player_collision_bounds,
-        },
-    ));
-
-    // UI
-    spawn_game_over_ui(commands);
-}
-
-pub fn play_music(asset_server: Res<AssetServer>, audio: Res<Audio>) {
-    let music = asset_server.load("audio/background_audio.ogg");
-    audio.play(music).looped();
+    for kind in SceneryKind::ALL {
+        assets.scenery.insert(kind.asset_path(), asset_server.load(kind.asset_path()));
+    }
+    for kind in PowerUp::ALL {
+        assets.power_ups.insert(kind.asset_path(), asset_server.load(kind.asset_path()));
+    }
+    for kind in Hazard::ALL {
+        assets.hazards.insert(kind.asset_path(), asset_server.load(kind.asset_path()));
+    }
+    for stage in &stages.0 {
+        let road = asset_server.load(stage.road_texture.as_str());
+        assets.roads.insert(stage.road_texture.clone(), road);
+    }
+    assets.road_line = asset_server.load("white_line.png");
+    assets.lane_ends_sign = asset_server.load("signs/lane_ends.png");
+    assets.coin = asset_server.load("coin.png");
+    assets.explosion = asset_server.load("explosion.png");
 }
 
-#[cfg(target_os = "windows")]
-pub fn set_windows_titlebar_icon(
+// Sets the window icon from the icon embedded in the binary, so it works on every
+// platform without reading assets/ at runtime. macOS ignores window icons.
+pub fn set_window_icon(
     window_query: Query<Entity, With<PrimaryWindow>>,
     winit_windows: NonSend<WinitWindows>,
 ) {
-    use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
-    use std::ffi::OsStr;
-    use std::iter::once;
-    use std::os::windows::ffi::OsStrExt;
-    use windows::Win32::Foundation::*;
-    use windows::Win32::UI::WindowsAndMessaging::*;
-
-    fn to_wide(s: &str) -> Vec<u16> {
-        OsStr::new(s).encode_wide().chain(once(0)).collect()
-    }
-
-    if let Ok(window_entity) = window_query.get_single() {
-        if let Some(winit_window) = winit_windows.get_window(window_entity) {
-            if let RawWindowHandle::Win32(handle) = winit_window.raw_window_handle() {
-                let hwnd = HWND(handle.hwnd as isize);
-                let icon_path = to_wide("assets/icon.ico");
-
-                unsafe {
-                    match LoadImageW(
-                        None,
-                        PCWSTR(icon_path.as_ptr()),
-                        IMAGE_ICON,
-                        32,
-                        32,
-                        LR_LOADFROMFILE,
-                    ) {
-                        Ok(hicon) => {
-                            SendMessageW(hwnd, WM_SETICON, WPARAM(ICON_BIG as usize), LPARAM(hicon.0 as isize));
-                            SendMessageW(hwnd, WM_SETICON, WPARAM(ICON_SMALL as usize), LPARAM(hicon.0 as isize));
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to load icon: {:?}", e);
-                        }
-                    }
-                }
+    let Ok(window_entity) = window_query.get_single() else {
+        return;
+    };
+    let Some(winit_window) = winit_windows.get_window(window_entity) else {
+        return;
+    };
+
+    let image = match image::load_from_memory_with_format(WINDOW_ICON, image::ImageFormat::Ico) {
+        Ok(image) => image.into_rgba8(),
+        Err(e) => {
+            eprintln!("Failed to load icon: {:?}", e);
+            return;
+        }
+    };
+    let (width, height) = image.dimensions();
+
+    match Icon::from_rgba(image.into_raw(), width, height) {
+        Ok(icon) => {
+            // The taskbar uses the large icon on Windows
+            #[cfg(target_os = "windows")]
+            {
+                use winit::platform::windows::WindowExtWindows;
+                winit_window.set_taskbar_icon(Some(icon.clone()));
             }
+            winit_window.set_window_icon(Some(icon));
+        }
+        Err(e) => {
+            eprintln!("Failed to create icon: {:?}", e);
         }
     }
-}
\ No newline at end of file
+}
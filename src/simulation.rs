@@ -0,0 +1,200 @@
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::time::Duration;
+use crate::components::{CollisionBounds, Enemy, Player};
+use crate::game::{GamePlugin, GameSet};
+use crate::playfield::HALF_HEIGHT;
+use crate::resources::{Difficulty, GameOver, GameRng, GameSpeed, PlayerInput};
+use crate::systems::RoadGeometry;
+
+// Fixed frame step used for simulated runs
+const SIMULATION_STEP: f32 = 1.0 / 60.0;
+// Runs that survive this long are stopped and counted at the cap
+const MAX_RUN_TIME: f32 = 300.0;
+// Spacing of the x positions the bot considers steering to
+const BOT_LANE_WIDTH: f32 = 50.0;
+const HISTOGRAM_BUCKET: f32 = 15.0;
+// Seed used when none is given, so reports can be compared between changes
+const DEFAULT_SEED: u64 = 0;
+// Runs per difficulty when no count is given
+const DEFAULT_RUNS: usize = 1000;
+
+pub struct SimulationConfig {
+    pub runs: usize,
+    pub difficulties: Vec<Difficulty>,
+    // Run n of every difficulty is seeded with seed + n
+    pub seed: u64,
+}
+
+impl SimulationConfig {
+    // Parses `--simulate [runs] [--difficulty easy|normal|hard] [--seed n]`.
+    // None when not simulating, an error for a value that doesn't parse.
+    pub fn from_args(args: &[String]) -> Option<Result<Self, String>> {
+        let simulate = args.iter().position(|arg| arg == "--simulate")?;
+        Some(Self::parse(args, simulate))
+    }
+
+    fn parse(args: &[String], simulate: usize) -> Result<Self, String> {
+        let runs = match args.get(simulate + 1).filter(|value| !value.starts_with("--")) {
+            Some(value) => match value.parse() {
+                Ok(runs) if runs > 0 => runs,
+                _ => return Err(format!("Invalid run count '{}', expected a number above 0", value)),
+            },
+            None => DEFAULT_RUNS,
+        };
+
+        let difficulties = match flag_value(args, "--difficulty") {
+            Some(name) => match Difficulty::from_name(name) {
+                Some(difficulty) => vec![difficulty],
+                None => return Err(format!("Unknown difficulty '{}', expected easy, normal or hard", name)),
+            },
+            None => Difficulty::ALL.to_vec(),
+        };
+
+        let seed = match flag_value(args, "--seed") {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("Invalid seed '{}', expected a whole number", value))?,
+            None => DEFAULT_SEED,
+        };
+
+        Ok(Self {
+            runs,
+            difficulties,
+            seed,
+        })
+    }
+}
+
+// The value after a flag, or "" when the flag is last
+fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    Some(args.get(index + 1).map(String::as_str).unwrap_or_default())
+}
+
+// The game is built as a Windows GUI app, which has no console to print the
+// report to. Use the one it was started from, or open a new one.
+#[cfg(windows)]
+pub fn attach_console() {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+        fn AllocConsole() -> i32;
+    }
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    unsafe {
+        if AttachConsole(ATTACH_PARENT_PROCESS) == 0 {
+            AllocConsole();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+pub fn run(config: SimulationConfig) {
+    for difficulty in config.difficulties {
+        let mut survival_times: Vec<f32> = (0..config.runs as u64)
+            .map(|run| simulate_run(difficulty, config.seed.wrapping_add(run)))
+            .collect();
+        survival_times.sort_by(|a, b| a.total_cmp(b));
+        print_report(difficulty, &survival_times);
+    }
+}
+
+// Plays a single run with the bot driver and returns its survival time. The same
+// seed always plays out the same run.
+pub fn simulate_run(difficulty: Difficulty, seed: u64) -> f32 {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, GamePlugin { difficulty }))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(SIMULATION_STEP)))
+        .insert_resource(GameRng::seeded(seed))
+        .add_systems(Update, bot_driver.in_set(GameSet::Input));
+    // Systems sharing the rng run in a fixed order
+    app.edit_schedule(Update, |schedule| {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    });
+
+    loop {
+        app.update();
+
+        let time_elapsed = app.world.resource::<GameSpeed>().time_elapsed;
+        if app.world.resource::<GameOver>().0 || time_elapsed >= MAX_RUN_TIME {
+            return time_elapsed;
+        }
+    }
+}
+
+// Steers toward the nearest x position with no enemy coming down on it
+pub fn bot_driver(
+    player_query: Query<(&Transform, &CollisionBounds), With<Player>>,
+    enemy_query: Query<(&Transform, &CollisionBounds), With<Enemy>>,
+    geometry: RoadGeometry,
+    mut input: ResMut<PlayerInput>,
+) {
+    let Ok((player_transform, player_bounds)) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation;
+
+    let is_blocked = |x: f32| {
+        enemy_query.iter().any(|(transform, bounds)| {
+            let enemy_pos = transform.translation;
+            let ahead = enemy_pos.y - player_pos.y;
+            let clearance = (player_bounds.size.x + bounds.size.x) / 2.0 + 10.0;
+            ahead > -player_bounds.size.y && ahead < HALF_HEIGHT && (enemy_pos.x - x).abs() < clearance
+        })
+    };
+
+    let (left, right) = geometry.at(player_pos.y).bounds();
+    let steps = ((right - left) / BOT_LANE_WIDTH) as i32;
+    let target = (0..=steps)
+        .map(|step| left + step as f32 * BOT_LANE_WIDTH)
+        .filter(|&x| !is_blocked(x))
+        .min_by(|a, b| (a - player_pos.x).abs().total_cmp(&(b - player_pos.x).abs()));
+
+    input.accelerate = false;
+    input.steer = match target {
+        Some(x) if (x - player_pos.x).abs() > 5.0 => (x - player_pos.x).signum(),
+        _ => 0.0,
+    };
+}
+
+fn print_report(difficulty: Difficulty, survival_times: &[f32]) {
+    let Some(&max) = survival_times.last() else {
+        return;
+    };
+    let percentile = |p: f32| survival_times[((survival_times.len() - 1) as f32 * p) as usize];
+    let mean = survival_times.iter().sum::<f32>() / survival_times.len() as f32;
+
+    println!("{:?} ({} runs)", difficulty, survival_times.len());
+    println!(
+        "  mean {:.1}s  min {:.1}s  p10 {:.1}s  median {:.1}s  p90 {:.1}s  max {:.1}s",
+        mean,
+        survival_times[0],
+        percentile(0.1),
+        percentile(0.5),
+        percentile(0.9),
+        max
+    );
+
+    let bucket_count = (max / HISTOGRAM_BUCKET) as usize + 1;
+    let mut buckets = vec![0; bucket_count];
+    for time in survival_times {
+        buckets[(time / HISTOGRAM_BUCKET) as usize] += 1;
+    }
+
+    for (i, count) in buckets.iter().enumerate() {
+        let bar_length = count * 50 / survival_times.len();
+        println!(
+            "  {:>4}-{:<4}s {:<50} {}",
+            i as f32 * HISTOGRAM_BUCKET,
+            (i + 1) as f32 * HISTOGRAM_BUCKET,
+            "#".repeat(bar_length),
+            count
+        );
+    }
+    println!();
+}
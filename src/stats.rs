@@ -0,0 +1,230 @@
+use bevy::prelude::*;
+use std::io;
+use crate::achievements::AchievementsMenu;
+use crate::components::{StatsText, StatsUI};
+use crate::game::GameSet;
+use crate::garage::GarageMenu;
+use crate::persistence;
+use crate::resources::{GameOver, InMenu, LifetimeStats, Settings, STATS_CSV_FILE, STATS_FILE, STATS_JSON_FILE};
+use crate::systems::record_lifetime_stats;
+use crate::ui::UiClick;
+
+// Loads and saves the lifetime stats, and shows them on a screen opened from
+// the Game Over screen that can export them to CSV or JSON
+pub struct StatsBoardPlugin;
+
+impl Plugin for StatsBoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatsMenu>()
+            .add_systems(Startup, (load_stats, spawn_stats_ui))
+            .add_systems(
+                Update,
+                (
+                    save_stats.after(record_lifetime_stats),
+                    (toggle_stats, export_stats, display_stats_screen).chain(),
+                )
+                    .in_set(GameSet::Ui),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct StatsMenu {
+    pub open: bool,
+    // Where the last export went, shown under the stats
+    pub exported: Option<String>,
+}
+
+pub fn load_stats(mut commands: Commands) {
+    if let Some(stats) = persistence::load::<LifetimeStats>(STATS_FILE) {
+        commands.insert_resource(stats);
+    }
+}
+
+pub fn save_stats(game_over: Res<GameOver>, lifetime: Res<LifetimeStats>) {
+    if game_over.is_changed() && game_over.0 {
+        persistence::save(STATS_FILE, &*lifetime);
+    }
+}
+
+pub fn spawn_stats_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.9)),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            StatsUI,
+        ))
+        .with_children(|parent| {
+            // Title
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Stats",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            });
+
+            // Filled in by display_stats_screen
+            parent.spawn((
+                TextBundle {
+                    text: Text::default(),
+                    style: Style {
+                        margin: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                StatsText,
+            ));
+
+            // Instructions
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "[C] Export CSV  [J] Export JSON  [T] Back  [R] Restart",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+}
+
+pub fn toggle_stats(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_over: Res<GameOver>,
+    in_menu: Res<InMenu>,
+    garage: Res<GarageMenu>,
+    achievements: Res<AchievementsMenu>,
+    mut menu: ResMut<StatsMenu>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !game_over.0 {
+        // Restarting always leaves the screen
+        if menu.open {
+            menu.open = false;
+        }
+    } else if keyboard_input.just_pressed(KeyCode::T) && !garage.open && !achievements.open && !in_menu.0 {
+        menu.open = !menu.open;
+        menu.exported = None;
+        click_events.send(UiClick);
+    }
+}
+
+pub fn export_stats(
+    keyboard_input: Res<Input<KeyCode>>,
+    in_menu: Res<InMenu>,
+    lifetime: Res<LifetimeStats>,
+    mut menu: ResMut<StatsMenu>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if !menu.open || in_menu.0 {
+        return;
+    }
+
+    let (file_name, result) = if keyboard_input.just_pressed(KeyCode::C) {
+        (STATS_CSV_FILE, persistence::write(STATS_CSV_FILE, &lifetime.to_csv()))
+    } else if keyboard_input.just_pressed(KeyCode::J) {
+        let result = serde_json::to_string_pretty(&*lifetime)
+            .map_err(io::Error::from)
+            .and_then(|json| persistence::write(STATS_JSON_FILE, &json));
+        (STATS_JSON_FILE, result)
+    } else {
+        return;
+    };
+    // Only point at the file once it is really there
+    menu.exported = match result {
+        Ok(()) => Some(persistence::save_path(file_name).display().to_string()),
+        Err(e) => {
+            eprintln!("Failed to export {}: {}", file_name, e);
+            None
+        }
+    };
+    click_events.send(UiClick);
+}
+
+fn stats_lines(lifetime: &LifetimeStats) -> String {
+    let mut lines = vec![
+        format!("Runs: {}", lifetime.runs),
+        format!("Total Distance: {:.1} km", lifetime.distance / 1000.0),
+        format!("Total Time: {:.0}s", lifetime.time),
+        format!("Average Survival: {:.1}s", lifetime.average_survival()),
+        format!("Best Speed: {:.0}", lifetime.best_speed),
+        format!("Near Misses: {}", lifetime.near_misses),
+        "Crashes:".to_string(),
+    ];
+    for (car_type, count) in lifetime.crashes_by_car() {
+        lines.push(format!("  {}: {}", car_type.name(), count));
+    }
+    if lifetime.barrier_crashes > 0 {
+        lines.push(format!("  Road hazards: {}", lifetime.barrier_crashes));
+    }
+    lines.join("\n")
+}
+
+pub fn display_stats_screen(
+    menu: Res<StatsMenu>,
+    lifetime: Res<LifetimeStats>,
+    settings: Res<Settings>,
+    mut ui_query: Query<&mut Visibility, With<StatsUI>>,
+    mut text_query: Query<&mut Text, With<StatsText>>,
+) {
+    if !menu.is_changed() && !lifetime.is_changed() && !settings.is_changed() {
+        return;
+    }
+
+    if let Ok(mut visibility) = ui_query.get_single_mut() {
+        *visibility = if menu.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let mut sections = vec![TextSection::new(
+            stats_lines(&lifetime),
+            TextStyle {
+                font_size: 24.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )];
+        if let Some(path) = &menu.exported {
+            sections.push(TextSection::new(
+                format!("\n\nExported to {}", path),
+                TextStyle {
+                    font_size: 20.0,
+                    color: settings.gameplay.palette().highlight,
+                    ..default()
+                },
+            ));
+        }
+        text.sections = sections;
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::UNITS_PER_METER;
+use crate::resources::{Achievement, Achievements, GameSpeed, Heat, LifetimeStats, OncomingTime, MAX_WANTED_LEVEL};
+use crate::systems::{check_collision, Crash, NearMiss};
+
+// The speed ramp's ceiling, which nitro and slow-mo do not count toward
+const TOP_MULTIPLIER: f32 = 4.0;
+const FULL_THROTTLE_SECONDS: f32 = 90.0;
+const WRECK_SECONDS: f32 = 3.0;
+const ROAD_TRIP_METERS: f32 = 10_000.0;
+const DAREDEVIL_NEAR_MISSES: u32 = 20;
+const WRONG_WAY_SECONDS: f32 = 30.0;
+
+// An achievement was unlocked for the first time
+#[derive(Event)]
+pub struct AchievementUnlocked {
+    pub achievement: Achievement,
+}
+
+// What the achievements need to know about the current run
+#[derive(Resource, Default)]
+pub struct AchievementRun {
+    pub time_at_top_speed: f32,
+    pub near_misses: u32,
+}
+
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Achievements>()
+            .init_resource::<AchievementRun>()
+            .add_event::<AchievementUnlocked>()
+            .add_systems(RunSetup, reset_achievement_run)
+            .add_systems(
+                Update,
+                // After the crash check, so the crash that ends a run still counts
+                check_achievements.after(check_collision).in_set(GameSet::Collision),
+            );
+    }
+}
+
+pub fn reset_achievement_run(mut run: ResMut<AchievementRun>) {
+    *run = AchievementRun::default();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn check_achievements(
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    oncoming_time: Res<OncomingTime>,
+    heat: Res<Heat>,
+    lifetime: Res<LifetimeStats>,
+    mut run: ResMut<AchievementRun>,
+    mut achievements: ResMut<Achievements>,
+    mut near_miss_events: EventReader<NearMiss>,
+    mut crash_events: EventReader<Crash>,
+    mut unlocked_events: EventWriter<AchievementUnlocked>,
+) {
+    if game_speed.ramp >= TOP_MULTIPLIER {
+        run.time_at_top_speed += time.delta_seconds();
+    }
+    run.near_misses += near_miss_events.iter().count() as u32;
+    let crashed = crash_events.iter().count() > 0;
+    let total_meters = lifetime.distance + game_speed.distance / UNITS_PER_METER;
+
+    let earned = [
+        (Achievement::FullThrottle, run.time_at_top_speed >= FULL_THROTTLE_SECONDS),
+        (Achievement::Wreck, crashed && game_speed.time_elapsed <= WRECK_SECONDS),
+        (Achievement::RoadTrip, total_meters >= ROAD_TRIP_METERS),
+        (Achievement::Daredevil, run.near_misses >= DAREDEVIL_NEAR_MISSES),
+        (Achievement::WrongWay, oncoming_time.0 >= WRONG_WAY_SECONDS),
+        (Achievement::MostWanted, heat.wanted_level() >= MAX_WANTED_LEVEL),
+    ];
+    for (achievement, done) in earned {
+        if done && !achievements.is_unlocked(achievement) {
+            achievements.unlock(achievement);
+            unlocked_events.send(AchievementUnlocked { achievement });
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::components::{Coin, CollisionBounds, Lane, Pickup, Player, RunScoped};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::SPAWN_Y;
+use crate::resources::{GameAssets, GameRng, RunCoins};
+use crate::systems::{aabb_collision, RoadGeometry};
+
+const COIN_SIZE: Vec2 = Vec2::new(24.0, 24.0);
+// Seconds between rows of coins
+const COIN_ROW_INTERVAL: f32 = 3.5;
+const COINS_PER_ROW: usize = 5;
+const COIN_SPACING: f32 = 40.0;
+
+#[derive(Resource)]
+pub struct CoinSpawnTimer(pub Timer);
+
+impl Default for CoinSpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(COIN_ROW_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+// The player picked up a coin
+#[derive(Event)]
+pub struct CoinCollected {
+    pub position: Vec3,
+}
+
+pub struct CoinPlugin;
+
+impl Plugin for CoinPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoinSpawnTimer>()
+            .add_event::<CoinCollected>()
+            .add_systems(RunSetup, reset_coin_timer)
+            .add_systems(
+                Update,
+                (
+                    spawn_coins.in_set(GameSet::Movement),
+                    collect_coins.in_set(GameSet::Collision),
+                ),
+            );
+    }
+}
+
+pub fn reset_coin_timer(mut timer: ResMut<CoinSpawnTimer>) {
+    timer.0.reset();
+}
+
+// Lays a row of coins down one open lane. They scroll and despawn as pickups.
+pub fn spawn_coins(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<CoinSpawnTimer>,
+    assets: Res<GameAssets>,
+    geometry: RoadGeometry,
+    mut rng: ResMut<GameRng>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let rng = &mut rng.0;
+    let shape = geometry.at(SPAWN_Y);
+    let lane = rng.random_range(0..shape.open_lanes());
+    for i in 0..COINS_PER_ROW {
+        let y = SPAWN_Y + i as f32 * COIN_SPACING;
+        commands.spawn((
+            SpriteBundle {
+                texture: assets.coin.clone(),
+                sprite: Sprite {
+                    custom_size: Some(COIN_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_xyz(geometry.at(y).lane_center(lane), y, 9.0),
+                ..default()
+            },
+            Coin,
+            Pickup,
+            Lane(lane),
+            CollisionBounds { size: COIN_SIZE },
+            RunScoped,
+        ));
+    }
+}
+
+pub fn collect_coins(
+    mut commands: Commands,
+    mut run_coins: ResMut<RunCoins>,
+    player_query: Query<(&Transform, &CollisionBounds), With<Player>>,
+    coin_query: Query<(Entity, &Transform, &CollisionBounds), With<Coin>>,
+    mut collected_events: EventWriter<CoinCollected>,
+) {
+    let Ok((player_transform, player_bounds)) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (entity, transform, bounds) in coin_query.iter() {
+        if aabb_collision(player_pos, player_bounds.size, transform.translation.truncate(), bounds.size) {
+            run_coins.0 += 1;
+            commands.entity(entity).despawn();
+            collected_events.send(CoinCollected {
+                position: transform.translation,
+            });
+        }
+    }
+}
@@ -1,27 +1,71 @@
-use crate::components::{Enemy, Explosion, Player, CollisionBounds};
-use crate::resources::GameOver;
+use crate::components::{Armor, CarType, Enemy, Explosion, Overtaken, Player, PowerUp, CollisionBounds, RunScoped, Solid};
+use crate::game::GameSet;
+use crate::resources::{ActiveEffects, GameAssets, GameOver};
 use bevy::prelude::*;
 
+// Horizontal gap between an overtaken enemy and the player that counts as a near miss
+const NEAR_MISS_DISTANCE: f32 = 20.0;
+
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Crash>()
+            .add_event::<ShieldHit>()
+            .add_event::<NearMiss>()
+            .add_systems(
+                Update,
+                (
+                    (check_collision, detect_near_misses).in_set(GameSet::Collision),
+                    explosion_cleanup_system.in_set(GameSet::Cleanup),
+                ),
+            );
+    }
+}
+
+// The player hit an enemy or a solid hazard
+#[derive(Event)]
+pub struct Crash {
+    pub position: Vec3,
+    // None for a hazard
+    pub car_type: Option<CarType>,
+}
+
+// The shield or the car's armor took a crash in the player's place
+#[derive(Event)]
+pub struct ShieldHit {
+    pub position: Vec3,
+}
+
+// An enemy passed the player within NEAR_MISS_DISTANCE
+#[derive(Event)]
+pub struct NearMiss {
+    pub position: Vec3,
+}
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn check_collision(
     mut commands: Commands,
     mut game_over: ResMut<GameOver>,
-    player_query: Query<(&Transform, &CollisionBounds), With<Player>>,
-    enemy_query: Query<(&Transform, &CollisionBounds), With<Enemy>>,
-    asset_server: Res<AssetServer>,
+    mut effects: ResMut<ActiveEffects>,
+    mut player_query: Query<(&Transform, &CollisionBounds, Option<&mut Armor>), With<Player>>,
+    enemy_query: Query<(Entity, &Transform, &CollisionBounds, Option<&CarType>), Or<(With<Enemy>, With<Solid>)>>,
+    assets: Res<GameAssets>,
+    mut crash_events: EventWriter<Crash>,
+    mut shield_events: EventWriter<ShieldHit>,
 ) {
     if game_over.0 {
         return; // already game over
     }
 
-    let Ok((player_transform, player_bounds)) = player_query.get_single() else {
+    let Ok((player_transform, player_bounds, mut armor)) = player_query.get_single_mut() else {
         return; // No player found
     };
 
     let player_pos = player_transform.translation;
     let player_size = player_bounds.size;
 
-    for (enemy_transform, enemy_bounds) in enemy_query.iter() {
+    for (enemy, enemy_transform, enemy_bounds, car_type) in enemy_query.iter() {
         let enemy_pos = enemy_transform.translation;
         let enemy_size = enemy_bounds.size;
 
@@ -29,19 +73,69 @@ pub fn check_collision(
         let collision = aabb_collision(player_pos.truncate(), player_size, enemy_pos.truncate(), enemy_size);
 
         if collision {
-            game_over.0 = true;
-
             // Spawn explosion at collision point
             let explosion_pos = (player_pos + enemy_pos) / 2.0;
-            spawn_explosion(&mut commands, &asset_server, explosion_pos);
+            spawn_explosion(&mut commands, &assets, explosion_pos);
+
+            // The shield wrecks the other car and is used up. Without one, armor
+            // takes the hit at the cost of a hit point.
+            let absorbed = if effects.is_active(PowerUp::Shield) {
+                effects.end(PowerUp::Shield);
+                true
+            } else if let Some(armor) = armor.as_mut().filter(|armor| armor.0 > 0) {
+                armor.0 -= 1;
+                true
+            } else {
+                false
+            };
+            if absorbed {
+                commands.entity(enemy).despawn_recursive();
+                shield_events.send(ShieldHit { position: explosion_pos });
+                continue;
+            }
+
+            game_over.0 = true;
+            crash_events.send(Crash {
+                position: explosion_pos,
+                car_type: car_type.copied(),
+            });
 
             break;
         }
     }
 }
 
+#[allow(clippy::type_complexity)]
+pub fn detect_near_misses(
+    mut commands: Commands,
+    player_query: Query<(&Transform, &CollisionBounds), With<Player>>,
+    enemy_query: Query<(Entity, &Transform, &CollisionBounds), (With<Enemy>, Without<Overtaken>)>,
+    mut near_miss_events: EventWriter<NearMiss>,
+) {
+    let Ok((player_transform, player_bounds)) = player_query.get_single() else {
+        return;
+    };
+
+    let player_pos = player_transform.translation;
+
+    for (entity, enemy_transform, enemy_bounds) in enemy_query.iter() {
+        let enemy_pos = enemy_transform.translation;
+
+        // Wait until the enemy is fully behind the player
+        if enemy_pos.y + enemy_bounds.size.y / 2.0 > player_pos.y - player_bounds.size.y / 2.0 {
+            continue;
+        }
+        commands.entity(entity).insert(Overtaken);
+
+        let gap = (enemy_pos.x - player_pos.x).abs() - (enemy_bounds.size.x + player_bounds.size.x) / 2.0;
+        if gap < NEAR_MISS_DISTANCE {
+            near_miss_events.send(NearMiss { position: enemy_pos });
+        }
+    }
+}
+
 // AABB collision detection
-fn aabb_collision(pos1: Vec2, size1: Vec2, pos2: Vec2, size2: Vec2) -> bool {
+pub fn aabb_collision(pos1: Vec2, size1: Vec2, pos2: Vec2, size2: Vec2) -> bool {
     let half_size1 = size1 / 2.0;
     let half_size2 = size2 / 2.0;
 
@@ -59,12 +153,12 @@ fn aabb_collision(pos1: Vec2, size1: Vec2, pos2: Vec2, size2: Vec2) -> bool {
     !(left1 >= right2 || right1 <= left2 || top1 <= bottom2 || bottom1 >= top2)
 }
 
-fn spawn_explosion(
+pub fn spawn_explosion(
     commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
+    assets: &GameAssets,
     position: Vec3,
 ) {
-    let texture_handle = asset_server.load("explosion.png");
+    let texture_handle = assets.explosion.clone();
 
     commands.spawn((
         SpriteBundle {
@@ -79,6 +173,7 @@ fn spawn_explosion(
         Explosion {
             timer: Timer::from_seconds(0.1, TimerMode::Once),
         },
+        RunScoped,
     ));
 }
 
@@ -1,55 +1,152 @@
 use bevy::prelude::*;
+use rand::seq::IndexedRandom;
 use rand::Rng;
-use crate::components::{Enemy, Velocity, CarType, CollisionBounds};
-use crate::resources::{EnemySpawnTimer, GameSpeed};
+use crate::components::{Enemy, Velocity, CarType, CollisionBounds, Lane, Oncoming, Player, PowerUp, RunScoped};
+use crate::game::GameSet;
+use crate::playfield::{DESPAWN_Y, SPAWN_Y};
+use crate::resources::{EnemySpawnTimer, GameAssets, GameRng, GameSpeed, OncomingTime, Score};
+use crate::systems::{pick_weighted, spawn_power_up, update_game_speed, RoadGeometry, Roadworks};
 
+const TRAFFIC_SPEED: f32 = 200.0;
+// Oncoming cars rush past much faster
+const ONCOMING_SPEED: f32 = 450.0;
+// How far traffic drops back along the road while it is on screen. Lanes with
+// roadworks this far ahead are kept clear.
+const ROADWORKS_CLEARANCE: f32 = 250.0;
+const ONCOMING_ROADWORKS_CLEARANCE: f32 = 700.0;
+// Score per second in an oncoming lane, times the speed multiplier
+const ONCOMING_POINTS_PER_SECOND: f32 = 25.0;
+// Chance that a power-up appears in another lane alongside a new car
+const POWER_UP_CHANCE: f64 = 0.12;
+
+pub struct TrafficPlugin;
+
+impl Plugin for TrafficPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                score_oncoming_driving.after(update_game_speed).in_set(GameSet::Progress),
+                (spawn_enemy_over_time, enemy_movement).in_set(GameSet::Movement),
+                cleanup_enemies.in_set(GameSet::Cleanup),
+            ),
+        );
+    }
+}
 
 pub fn spawn_enemy_over_time(
     mut commands: Commands,
     time: Res<Time>,
     mut timer: ResMut<EnemySpawnTimer>,
-    asset_server: Res<AssetServer>,
+    assets: Res<GameAssets>,
+    roadworks: Res<Roadworks>,
+    geometry: RoadGeometry,
+    mut rng: ResMut<GameRng>,
 ) {
     if timer.0.tick(time.delta()).just_finished() {
-        let mut rng = rand::rng();
-        let enemy_index = rng.random_range(1..=5);
-        let x = rng.random_range(-300.0..300.0);
+        let rng = &mut rng.0;
+        // Traffic keeps to the middle of the lanes open where it appears, away from roadworks
+        let shape = geometry.at(SPAWN_Y);
+        let spawn_distance = geometry.distance_at(SPAWN_Y);
+        let oncoming_lanes = geometry.stage().oncoming_lanes;
+        let lanes: Vec<usize> = (0..shape.open_lanes())
+            .filter(|lane| {
+                let clearance = if *lane < oncoming_lanes {
+                    ONCOMING_ROADWORKS_CLEARANCE
+                } else {
+                    ROADWORKS_CLEARANCE
+                };
+                !roadworks.closes(*lane, spawn_distance - clearance, spawn_distance)
+            })
+            .collect();
+        let lane = lanes.choose(rng).copied().unwrap_or(0);
+        let x = shape.lane_center(lane);
+        let oncoming = lane < oncoming_lanes;
 
-        let car_type = CarType::from_enemy_index(enemy_index);
+        let car_type = pick_weighted(&geometry.stage().traffic, rng).unwrap_or(CarType::TealSedan);
         let collision_bounds = car_type.collision_bounds();
+        // The sprites face up the screen, so oncoming cars are flipped
+        let scale = car_type.sprite_scale();
 
-        commands.spawn((
+        let mut enemy = commands.spawn((
             SpriteBundle {
-                texture: asset_server.load(car_type.asset_path()),
+                texture: assets.car(car_type),
                 transform: Transform {
-                    translation: Vec3::new(x, 350.0, 10.0),
-                    scale: Vec3::new(0.2, -0.2, 1.0),
+                    translation: Vec3::new(x, SPAWN_Y, 10.0),
+                    scale: Vec3::new(scale, if oncoming { -scale } else { scale }, 1.0),
                     ..default()
                 },
                 ..default()
             },
             Enemy,
-            Velocity { speed: 200.0 },
+            Lane(lane),
+            Velocity {
+                speed: if oncoming { ONCOMING_SPEED } else { TRAFFIC_SPEED },
+            },
             car_type,
             CollisionBounds {
                 size: collision_bounds,
             },
+            RunScoped,
         ));
+        if oncoming {
+            enemy.insert(Oncoming);
+        }
+
+        let open_lanes = shape.open_lanes();
+        if open_lanes > 1 && rng.random_bool(POWER_UP_CHANCE) {
+            let power_up_lane = (lane + rng.random_range(1..open_lanes)) % open_lanes;
+            let kind = *PowerUp::ALL.choose(rng).unwrap();
+            let position = Vec3::new(shape.lane_center(power_up_lane), SPAWN_Y, 9.0);
+            spawn_power_up(&mut commands, &assets, kind, power_up_lane, position);
+        }
     }
 }
 
+// Traffic follows its lane through curves and merges out of closing lanes
 pub fn enemy_movement(
-    mut enemies: Query<(&mut Transform, &Velocity), With<Enemy>>,
+    mut enemies: Query<(&mut Transform, &Velocity, Option<&Lane>), With<Enemy>>,
     time: Res<Time>,
     game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
 ) {
-    for (mut transform, velocity) in enemies.iter_mut() {
+    for (mut transform, velocity, lane) in enemies.iter_mut() {
         transform.translation.y -= velocity.speed * game_speed.multiplier * time.delta_seconds();
+        if let Some(lane) = lane {
+            transform.translation.x = geometry.at(transform.translation.y).lane_center(lane.0);
+        }
     }
 }
+
+// Driving against the traffic is worth extra points for as long as it lasts
+pub fn score_oncoming_driving(
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
+    player_query: Query<&Transform, With<Player>>,
+    mut score: ResMut<Score>,
+    mut oncoming_time: ResMut<OncomingTime>,
+) {
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+    // One-way roads have nothing to drive against, even on the margin past the left edge
+    let oncoming_lanes = geometry.stage().oncoming_lanes;
+    if oncoming_lanes == 0 {
+        return;
+    }
+    let shape = geometry.at(transform.translation.y);
+    let oncoming_edge = shape.left_edge() + oncoming_lanes as f32 * shape.lane_width;
+    if transform.translation.x < oncoming_edge {
+        let dt = time.delta_seconds();
+        oncoming_time.0 += dt;
+        score.0 += ONCOMING_POINTS_PER_SECOND * game_speed.multiplier * dt;
+    }
+}
+
 pub fn cleanup_enemies(mut commands: Commands, query: Query<(Entity, &Transform), With<Enemy>>) {
     for (entity, transform) in query.iter() {
-        if transform.translation.y < -350.0 {
+        if transform.translation.y < DESPAWN_Y {
             commands.entity(entity).despawn();
         }
     }
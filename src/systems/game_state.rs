@@ -1,9 +1,32 @@
 use bevy::prelude::*;
-use crate::resources::GameSpeed;
+use crate::components::{Player, Velocity};
+use crate::playfield::UNITS_PER_METER;
+use crate::resources::{ActiveEffects, Difficulty, GameMode, GameSpeed, Score};
+use crate::systems::{PLAYER_BASE_SPEED, ROAD_SPEED};
 
-pub fn update_game_speed(mut game_speed: ResMut<GameSpeed>, time: Res<Time>) {
+// The speed ramp tops out here, and nitro cannot push past it
+pub const MAX_MULTIPLIER: f32 = 4.0;
+
+pub fn update_game_speed(
+    mut game_speed: ResMut<GameSpeed>,
+    mut score: ResMut<Score>,
+    time: Res<Time>,
+    difficulty: Res<Difficulty>,
+    effects: Res<ActiveEffects>,
+    mode: Res<GameMode>,
+    player_query: Query<&Velocity, With<Player>>,
+) {
     game_speed.time_elapsed += time.delta_seconds();
 
-    // Increase multiplier gradually
-    game_speed.multiplier = 1.0 + (game_speed.time_elapsed / 30.0).min(3.0); // max 4x after 90s
+    // Increase multiplier gradually, then let nitro and slow-mo bend it. In a
+    // time trial the road goes by as fast as the player drives instead.
+    game_speed.ramp = match (*mode, player_query.get_single()) {
+        (GameMode::TimeTrial, Ok(velocity)) => (velocity.speed / PLAYER_BASE_SPEED).clamp(1.0, MAX_MULTIPLIER),
+        _ => (1.0 + game_speed.time_elapsed / difficulty.speed_ramp_time()).min(MAX_MULTIPLIER),
+    };
+    game_speed.multiplier = (game_speed.ramp * effects.speed_factor()).min(MAX_MULTIPLIER);
+
+    let travelled = ROAD_SPEED * game_speed.multiplier * time.delta_seconds();
+    game_speed.distance += travelled;
+    score.0 += travelled / UNITS_PER_METER * effects.score_factor();
 }
@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use crate::components::{CarType, Ghost, Player, RunScoped};
+use crate::game::{GameSet, RunSetup};
+use crate::persistence;
+use crate::systems::{in_time_trial, PLAYER_START};
+use crate::resources::{
+    GameAssets, GameOver, GameSpeed, GhostData, HighScore, GHOST_FILE, GHOST_SAMPLE_INTERVAL,
+    HIGH_SCORE_FILE,
+};
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_personal_best)
+            .add_systems(RunSetup, (spawn_ghost, reset_ghost_recording))
+            .add_systems(
+                Update,
+                (
+                    update_ghost.in_set(GameSet::Movement),
+                    record_player_position.in_set(GameSet::Cleanup),
+                    // Time trials end at the finish, so they don't count as survival times
+                    save_personal_best.run_if(not(in_time_trial)).in_set(GameSet::Ui),
+                ),
+            );
+    }
+}
+
+pub fn load_personal_best(mut commands: Commands) {
+    let high_score: HighScore = persistence::load(HIGH_SCORE_FILE).unwrap_or_default();
+    let ghost_data = GhostData {
+        best: persistence::load(GHOST_FILE),
+        ..default()
+    };
+
+    commands.insert_resource(high_score);
+    commands.insert_resource(ghost_data);
+}
+
+pub fn spawn_ghost(mut commands: Commands, assets: Res<GameAssets>) {
+    commands.spawn((
+        SpriteBundle {
+            texture: assets.car(CarType::Player),
+            sprite: Sprite {
+                color: Color::rgba(1.0, 1.0, 1.0, 0.35),
+                ..default()
+            },
+            transform: Transform {
+                translation: PLAYER_START.truncate().extend(9.0),
+                scale: Vec3::splat(0.1),
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        Ghost,
+        RunScoped,
+    ));
+}
+
+pub fn record_player_position(
+    player_query: Query<&Transform, With<Player>>,
+    game_speed: Res<GameSpeed>,
+    mut ghost_data: ResMut<GhostData>,
+) {
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+
+    // Catch up on every sample interval passed since the last frame
+    while ghost_data.current.positions.len() as f32 * GHOST_SAMPLE_INTERVAL <= game_speed.time_elapsed {
+        ghost_data.current.positions.push(transform.translation.truncate().into());
+    }
+}
+
+pub fn update_ghost(
+    game_speed: Res<GameSpeed>,
+    ghost_data: Res<GhostData>,
+    mut ghost_query: Query<(&mut Transform, &mut Visibility), With<Ghost>>,
+) {
+    let Ok((mut transform, mut visibility)) = ghost_query.get_single_mut() else {
+        return;
+    };
+
+    // Hide the ghost once the best run has crashed
+    match ghost_data.best.as_ref().and_then(|best| best.position_at(game_speed.time_elapsed)) {
+        Some(position) => {
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+            *visibility = Visibility::Visible;
+        }
+        None => *visibility = Visibility::Hidden,
+    }
+}
+
+pub fn save_personal_best(
+    game_over: Res<GameOver>,
+    game_speed: Res<GameSpeed>,
+    mut high_score: ResMut<HighScore>,
+    mut ghost_data: ResMut<GhostData>,
+) {
+    if game_over.is_changed() && game_over.0 && game_speed.time_elapsed > high_score.best_time {
+        high_score.best_time = game_speed.time_elapsed;
+        ghost_data.best = Some(ghost_data.current.clone());
+
+        persistence::save(HIGH_SCORE_FILE, &*high_score);
+        persistence::save(GHOST_FILE, &ghost_data.current);
+    }
+}
+
+// Start a fresh recording for the new run
+pub fn reset_ghost_recording(mut ghost_data: ResMut<GhostData>) {
+    ghost_data.current.positions.clear();
+}
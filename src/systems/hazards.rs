@@ -0,0 +1,270 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::components::{
+    Armor, CollisionBounds, Hazard, Lane, LateralVelocity, Player, PowerUp, RunScoped, Skid, Solid, Spent, Velocity,
+};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::{DESPAWN_Y, SPAWN_Y};
+use crate::resources::{ActiveEffects, GameAssets, GameOver, GameRng, GameSpeed};
+use crate::systems::{
+    aabb_collision, check_collision, spawn_explosion, Crash, RoadGeometry, ShieldHit, PLAYER_BASE_SPEED, ROAD_SPEED,
+};
+
+// Seconds between hazards
+const HAZARD_INTERVAL: f32 = 2.5;
+// Odds of each kind of hazard, with roadworks as the last option
+const OIL_WEIGHT: u32 = 3;
+const POTHOLE_WEIGHT: u32 = 3;
+const CONE_WEIGHT: u32 = 2;
+const ROADWORKS_WEIGHT: u32 = 2;
+const ROADWORKS_LENGTH: f32 = 480.0;
+const CONE_SPACING: f32 = 60.0;
+// Seconds without steering after hitting oil, and the sideways shove it gives
+const OIL_SKID_SECONDS: f32 = 1.2;
+const OIL_KICK: f32 = 120.0;
+// Share of the car's speed left after a pothole
+const POTHOLE_SLOWDOWN: f32 = 0.6;
+const POTHOLE_JOLT: f32 = 80.0;
+
+#[derive(Resource)]
+pub struct HazardSpawnTimer(pub Timer);
+
+impl Default for HazardSpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(HAZARD_INTERVAL, TimerMode::Repeating))
+    }
+}
+
+// A lane closed by roadworks between two distances along the road
+#[derive(Clone, Copy)]
+pub struct RoadworksZone {
+    pub lane: usize,
+    pub start: f32,
+    pub end: f32,
+}
+
+// Roadworks still on or ahead of the screen. Traffic keeps out of their lanes.
+#[derive(Resource, Default)]
+pub struct Roadworks {
+    zones: Vec<RoadworksZone>,
+}
+
+impl Roadworks {
+    pub fn clear(&mut self) {
+        self.zones.clear();
+    }
+
+    // Whether roadworks close the lane anywhere between two distances
+    pub fn closes(&self, lane: usize, from: f32, to: f32) -> bool {
+        self.zones
+            .iter()
+            .any(|zone| zone.lane == lane && zone.start <= to && zone.end >= from)
+    }
+}
+
+// The player ran into a hazard that is not solid
+#[derive(Event)]
+pub struct HazardHit {
+    pub kind: Hazard,
+    pub position: Vec3,
+}
+
+pub struct HazardPlugin;
+
+impl Plugin for HazardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HazardSpawnTimer>()
+            .add_event::<HazardHit>()
+            .add_systems(RunSetup, reset_hazard_timer)
+            .add_systems(
+                Update,
+                (
+                    (spawn_hazards, scroll_hazards).chain().in_set(GameSet::Movement),
+                    hit_hazards.before(check_collision).in_set(GameSet::Collision),
+                    cleanup_hazards.in_set(GameSet::Cleanup),
+                ),
+            );
+    }
+}
+
+pub fn reset_hazard_timer(mut timer: ResMut<HazardSpawnTimer>) {
+    timer.0.reset();
+}
+
+fn spawn_hazard(commands: &mut Commands, assets: &GameAssets, kind: Hazard, lane: usize, position: Vec3) {
+    let mut entity = commands.spawn((
+        SpriteBundle {
+            texture: assets.hazard(kind),
+            sprite: Sprite {
+                custom_size: Some(kind.size()),
+                ..default()
+            },
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        kind,
+        Lane(lane),
+        CollisionBounds { size: kind.size() },
+        RunScoped,
+    ));
+    if kind.is_solid() {
+        entity.insert(Solid);
+    }
+}
+
+// Drops a single hazard in a random lane, or starts roadworks that close a lane
+// with a barrier followed by a line of cones
+pub fn spawn_hazards(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<HazardSpawnTimer>,
+    mut roadworks: ResMut<Roadworks>,
+    assets: Res<GameAssets>,
+    geometry: RoadGeometry,
+    mut rng: ResMut<GameRng>,
+) {
+    // Forget roadworks once they are below the screen
+    let bottom = geometry.distance_at(DESPAWN_Y);
+    roadworks.zones.retain(|zone| zone.end > bottom);
+
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let rng = &mut rng.0;
+    let shape = geometry.at(SPAWN_Y);
+    let roll = rng.random_range(0..OIL_WEIGHT + POTHOLE_WEIGHT + CONE_WEIGHT + ROADWORKS_WEIGHT);
+    let kind = if roll < OIL_WEIGHT {
+        Hazard::OilSlick
+    } else if roll < OIL_WEIGHT + POTHOLE_WEIGHT {
+        Hazard::Pothole
+    } else if roll < OIL_WEIGHT + POTHOLE_WEIGHT + CONE_WEIGHT {
+        Hazard::Cone
+    } else {
+        Hazard::Barrier
+    };
+
+    if kind != Hazard::Barrier {
+        let lane = rng.random_range(0..shape.open_lanes());
+        let position = Vec3::new(shape.lane_center(lane), SPAWN_Y, 2.0);
+        spawn_hazard(&mut commands, &assets, kind, lane, position);
+        return;
+    }
+
+    // Roadworks always leave a lane open, and never start where lanes are merging
+    let end_y = SPAWN_Y + ROADWORKS_LENGTH;
+    let open_lanes = shape.open_lanes().min(geometry.at(end_y).open_lanes());
+    if open_lanes < 2 || shape.lanes.fract() > 0.0 {
+        return;
+    }
+    let lane = rng.random_range(0..open_lanes);
+    let start = geometry.distance_at(SPAWN_Y);
+    roadworks.zones.push(RoadworksZone {
+        lane,
+        start,
+        end: start + ROADWORKS_LENGTH,
+    });
+
+    spawn_hazard(&mut commands, &assets, Hazard::Barrier, lane, Vec3::new(shape.lane_center(lane), SPAWN_Y, 2.0));
+    let mut y = SPAWN_Y + CONE_SPACING;
+    while y <= end_y {
+        let x = geometry.at(y).lane_center(lane);
+        spawn_hazard(&mut commands, &assets, Hazard::Cone, lane, Vec3::new(x, y, 2.0));
+        y += CONE_SPACING;
+    }
+}
+
+// Hazards lie still on the road, so they scroll with it and follow their lane
+pub fn scroll_hazards(
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
+    mut hazards: Query<(&mut Transform, &Lane), With<Hazard>>,
+) {
+    for (mut transform, lane) in hazards.iter_mut() {
+        transform.translation.y -= ROAD_SPEED * game_speed.multiplier * time.delta_seconds();
+        transform.translation.x = geometry.at(transform.translation.y).lane_center(lane.0);
+    }
+}
+
+// Each hazard has its own effect. Solid ones are left to check_collision.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn hit_hazards(
+    mut commands: Commands,
+    mut game_over: ResMut<GameOver>,
+    mut effects: ResMut<ActiveEffects>,
+    mut player_query: Query<
+        (
+            &Transform,
+            &CollisionBounds,
+            &mut Velocity,
+            &mut LateralVelocity,
+            &mut Skid,
+            Option<&mut Armor>,
+        ),
+        With<Player>,
+    >,
+    hazard_query: Query<(Entity, &Transform, &CollisionBounds, &Hazard), (Without<Spent>, Without<Solid>)>,
+    assets: Res<GameAssets>,
+    mut hit_events: EventWriter<HazardHit>,
+    mut crash_events: EventWriter<Crash>,
+    mut shield_events: EventWriter<ShieldHit>,
+    mut rng: ResMut<GameRng>,
+) {
+    let Ok((player_transform, player_bounds, mut velocity, mut lateral, mut skid, mut armor)) =
+        player_query.get_single_mut()
+    else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (entity, transform, bounds, kind) in hazard_query.iter() {
+        if !aabb_collision(player_pos, player_bounds.size, transform.translation.truncate(), bounds.size) {
+            continue;
+        }
+
+        match kind {
+            Hazard::OilSlick => {
+                skid.0 = OIL_SKID_SECONDS;
+                lateral.0 += if rng.0.random_bool(0.5) { OIL_KICK } else { -OIL_KICK };
+            }
+            Hazard::Pothole => {
+                velocity.speed = (velocity.speed * POTHOLE_SLOWDOWN).max(PLAYER_BASE_SPEED);
+                lateral.0 += if rng.0.random_bool(0.5) { POTHOLE_JOLT } else { -POTHOLE_JOLT };
+                // Armor takes the jolt, then the shield. With neither the car is wrecked.
+                if let Some(armor) = armor.as_mut().filter(|armor| armor.0 > 0) {
+                    armor.0 -= 1;
+                } else if effects.is_active(PowerUp::Shield) {
+                    effects.end(PowerUp::Shield);
+                    shield_events.send(ShieldHit {
+                        position: transform.translation,
+                    });
+                } else {
+                    spawn_explosion(&mut commands, &assets, transform.translation);
+                    game_over.0 = true;
+                    crash_events.send(Crash {
+                        position: transform.translation,
+                        car_type: None,
+                    });
+                }
+            }
+            Hazard::Cone | Hazard::Barrier => continue,
+        }
+        commands.entity(entity).insert(Spent);
+        hit_events.send(HazardHit {
+            kind: *kind,
+            position: transform.translation,
+        });
+        if game_over.0 {
+            break;
+        }
+    }
+}
+
+pub fn cleanup_hazards(mut commands: Commands, query: Query<(Entity, &Transform), With<Hazard>>) {
+    for (entity, transform) in query.iter() {
+        if transform.translation.y < DESPAWN_Y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
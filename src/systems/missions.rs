@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::UNITS_PER_METER;
+use crate::resources::{GameSpeed, MissionKind, Missions, OncomingTime, Profile};
+use crate::systems::{CoinCollected, Crash, HazardHit, NearMiss, PowerUpCollected, ShieldHit};
+
+// A mission was finished and its reward paid into the wallet
+#[derive(Event)]
+pub struct MissionCompleted {
+    pub description: String,
+    pub reward: u32,
+}
+
+// Distance at which the player last got a scratch this run
+#[derive(Resource, Default)]
+pub struct LastScratch(pub f32);
+
+pub struct MissionPlugin;
+
+impl Plugin for MissionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Missions>()
+            .init_resource::<LastScratch>()
+            .add_event::<MissionCompleted>()
+            .add_systems(RunSetup, start_run_missions)
+            .add_systems(
+                Update,
+                (track_missions, complete_missions).chain().in_set(GameSet::Cleanup),
+            );
+    }
+}
+
+// Missions that must be done within one run start over
+pub fn start_run_missions(mut missions: ResMut<Missions>, mut last_scratch: ResMut<LastScratch>) {
+    for mission in missions.active.iter_mut().filter(|mission| mission.kind.per_run()) {
+        mission.progress = 0.0;
+    }
+    last_scratch.0 = 0.0;
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn track_missions(
+    game_speed: Res<GameSpeed>,
+    oncoming_time: Res<OncomingTime>,
+    mut missions: ResMut<Missions>,
+    mut last_scratch: ResMut<LastScratch>,
+    mut near_miss_events: EventReader<NearMiss>,
+    mut power_up_events: EventReader<PowerUpCollected>,
+    mut coin_events: EventReader<CoinCollected>,
+    mut shield_events: EventReader<ShieldHit>,
+    mut hazard_events: EventReader<HazardHit>,
+    mut crash_events: EventReader<Crash>,
+) {
+    let near_misses = near_miss_events.iter().count() as f32;
+    let power_ups: Vec<_> = power_up_events.iter().map(|event| event.kind).collect();
+    let coins = coin_events.iter().count() as f32;
+    let scratches = shield_events.iter().count() + hazard_events.iter().count() + crash_events.iter().count();
+    if scratches > 0 {
+        last_scratch.0 = game_speed.distance;
+    }
+    let clean_meters = (game_speed.distance - last_scratch.0) / UNITS_PER_METER;
+
+    // Missions are only marked changed when progress moves, so their text is
+    // not rebuilt every frame
+    let mut changed = false;
+    for mission in missions.bypass_change_detection().active.iter_mut() {
+        let progress = match mission.kind {
+            MissionKind::NearMisses { .. } => mission.progress + near_misses,
+            MissionKind::CollectPowerUps { kind, .. } => {
+                mission.progress + power_ups.iter().filter(|&&collected| collected == kind).count() as f32
+            }
+            MissionKind::CollectCoins { .. } => mission.progress + coins,
+            MissionKind::CleanDistance { .. } => mission.progress.max(clean_meters),
+            MissionKind::OncomingTime { .. } => oncoming_time.0,
+            MissionKind::Survive { .. } => game_speed.time_elapsed,
+        };
+        if progress != mission.progress {
+            mission.progress = progress;
+            changed = true;
+        }
+    }
+    if changed {
+        missions.set_changed();
+    }
+}
+
+// Pays out finished missions and replaces them with new ones
+pub fn complete_missions(
+    mut missions: ResMut<Missions>,
+    mut profile: ResMut<Profile>,
+    mut completed_events: EventWriter<MissionCompleted>,
+) {
+    if !missions.active.iter().any(|mission| mission.is_complete()) {
+        return;
+    }
+
+    missions.active.retain(|mission| {
+        if !mission.is_complete() {
+            return true;
+        }
+        let reward = mission.kind.reward();
+        profile.coins += reward;
+        completed_events.send(MissionCompleted {
+            description: mission.kind.description(),
+            reward,
+        });
+        false
+    });
+    missions.fill(&mut rand::rng());
+}
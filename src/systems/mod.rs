@@ -1,11 +1,35 @@
+pub mod achievements;
+pub mod coins;
 pub mod collision;
 pub mod enemy;
 pub mod game_state;
+pub mod ghost;
+pub mod hazards;
+pub mod missions;
 pub mod movement;
+pub mod police;
+pub mod powerup;
 pub mod road;
+pub mod road_layout;
+pub mod stage;
+pub mod stats;
+pub mod time_trial;
+pub mod weather;
 
+pub use achievements::*;
+pub use coins::*;
 pub use collision::*;
 pub use enemy::*;
 pub use game_state::*;
+pub use ghost::*;
+pub use hazards::*;
+pub use missions::*;
 pub use movement::*;
-pub use road::*;
\ No newline at end of file
+pub use police::*;
+pub use powerup::*;
+pub use road::*;
+pub use road_layout::*;
+pub use stage::*;
+pub use stats::*;
+pub use time_trial::*;
+pub use weather::*;
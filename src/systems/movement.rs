@@ -1,34 +1,111 @@
 use bevy::prelude::*;
-use crate::components::{Player, Velocity};
+use crate::components::{Armor, CarStats, CollisionBounds, LateralVelocity, Player, RunScoped, Skid, Velocity};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::HALF_HEIGHT;
+use crate::resources::{Atmosphere, GameAssets, GameMode, PlayerInput, Profile};
+use crate::systems::RoadGeometry;
 
+pub const PLAYER_START: Vec3 = Vec3::new(0.0, -HALF_HEIGHT + 100.0, 10.0);
+// Speed every run starts at, before accelerating
+pub const PLAYER_BASE_SPEED: f32 = 150.0;
+// How quickly the car's sideways speed follows the steering with full grip
+const STEER_RESPONSE: f32 = 20.0;
+// Speed lost per second off the throttle in a time trial
+const COAST_DECELERATION: f32 = 200.0;
 
-pub fn player_movement(
-    keyboard_input: Res<Input<KeyCode>>,
-    mut query: Query<(&mut Transform, &mut Velocity), With<Player>>,
-    time: Res<Time>,
-) {
-    let (mut transform, mut velocity) = query.single_mut();
-    let mut direction = Vec3::ZERO;
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(RunSetup, spawn_player).add_systems(
+            Update,
+            (
+                keyboard_input
+                    .run_if(resource_exists::<Input<KeyCode>>())
+                    .in_set(GameSet::Input),
+                player_movement.in_set(GameSet::Movement),
+            ),
+        );
+    }
+}
+
+// The player drives the car selected in the garage, with its hitbox and its
+// stats after upgrades
+pub fn spawn_player(mut commands: Commands, assets: Res<GameAssets>, profile: Res<Profile>) {
+    let player_car_type = profile.selected_car;
+    let player_collision_bounds = player_car_type.collision_bounds();
+    let upgrades = profile.upgrades(player_car_type);
+
+    commands.spawn((
+        SpriteBundle {
+            texture: assets.car(player_car_type),
+            transform: Transform {
+                translation: PLAYER_START,
+                scale: Vec3::splat(player_car_type.sprite_scale()),
+                ..default()
+            },
+            ..default()
+        },
+        Player,
+        Velocity {
+            speed: PLAYER_BASE_SPEED,
+        },
+        LateralVelocity::default(),
+        Skid::default(),
+        player_car_type,
+        upgrades.apply(player_car_type.stats()),
+        Armor(upgrades.armor as u32),
+        CollisionBounds {
+            size: player_collision_bounds,
+        },
+        RunScoped,
+    ));
+}
+
+pub fn keyboard_input(keyboard_input: Res<Input<KeyCode>>, mut input: ResMut<PlayerInput>) {
+    input.steer = 0.0;
 
     if keyboard_input.pressed(KeyCode::A) || keyboard_input.pressed(KeyCode::Left) {
-        direction.x -= 0.2;
+        input.steer -= 1.0;
     }
     if keyboard_input.pressed(KeyCode::D) || keyboard_input.pressed(KeyCode::Right) {
-        direction.x += 0.2;
-    }
-    if keyboard_input.pressed(KeyCode::W) || keyboard_input.pressed(KeyCode::Up) {
-        velocity.speed += 10.0;
+        input.steer += 1.0;
     }
+    input.accelerate = keyboard_input.pressed(KeyCode::W) || keyboard_input.pressed(KeyCode::Up);
+}
+
+pub fn player_movement(
+    input: Res<PlayerInput>,
+    atmosphere: Res<Atmosphere>,
+    mode: Res<GameMode>,
+    mut query: Query<(&mut Transform, &mut Velocity, &mut LateralVelocity, &mut Skid, &CarStats), With<Player>>,
+    time: Res<Time>,
+    geometry: RoadGeometry,
+) {
+    let (mut transform, mut velocity, mut lateral, mut skid, stats) = query.single_mut();
+    let dt = time.delta_seconds();
 
-    // Normalize direction
-    if direction != Vec3::ZERO {
-        direction = direction.normalize();
+    if input.accelerate {
+        velocity.speed = (velocity.speed + stats.acceleration * dt).min(stats.top_speed);
+    } else if *mode == GameMode::TimeTrial {
+        // The road follows the car here, so easing off loses ground on the clock
+        velocity.speed = (velocity.speed - COAST_DECELERATION * dt).max(PLAYER_BASE_SPEED);
     }
 
-    // Move player using their speed
-    transform.translation += direction * velocity.speed * time.delta_seconds();
+    // Sideways speed eases toward the steering; the less grip, the slower it follows.
+    // Skidding on oil, the car just slides on.
+    let grip = if skid.0 > 0.0 { 0.0 } else { atmosphere.grip() };
+    skid.0 = (skid.0 - dt).max(0.0);
+    let target = input.steer.clamp(-1.0, 1.0) * velocity.speed;
+    let response = (STEER_RESPONSE * stats.handling * grip * dt).min(1.0);
+    lateral.0 += (target - lateral.0) * response;
+    transform.translation.x += lateral.0 * dt;
 
-    // Clamp to road bounds
-    const ROAD_BOUNDS: f32 = 350.0;
-    transform.translation.x = transform.translation.x.clamp(-ROAD_BOUNDS, ROAD_BOUNDS);
+    // Clamp to the road where the car is, losing the sideways speed against the edge
+    let (left, right) = geometry.at(transform.translation.y).bounds();
+    let clamped = transform.translation.x.clamp(left, right);
+    if clamped != transform.translation.x {
+        transform.translation.x = clamped;
+        lateral.0 = 0.0;
+    }
 }
@@ -0,0 +1,213 @@
+use bevy::prelude::*;
+use rand::Rng;
+use crate::components::{CarType, CollisionBounds, Player, Police, PoliceRole, RunScoped, SirenLight, Solid, Velocity};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::HALF_HEIGHT;
+use crate::resources::{GameAssets, GameRng, Heat, MAX_WANTED_LEVEL};
+use crate::systems::{NearMiss, RoadGeometry};
+
+const HEAT_PER_NEAR_MISS: f32 = 0.4;
+// Driving faster than this builds heat every second
+const SPEEDING_SPEED: f32 = 320.0;
+const SPEEDING_HEAT: f32 = 0.3;
+// Heat lost per second while no police are chasing
+const HEAT_DECAY: f32 = 0.1;
+// Police close in at this speed, so a player driving faster pulls away
+const POLICE_SPEED: f32 = 260.0;
+// A little slower sideways than the player at the start of a run, so rams can be dodged
+const POLICE_STEER_SPEED: f32 = 140.0;
+// Police appear, and trail at most, this far below the screen
+const POLICE_TRAIL: f32 = 120.0;
+const POLICE_SPAWN_COOLDOWN: f32 = 1.5;
+// Police further behind the player than this are losing them. Keeping all of
+// them there for ESCAPE_SECONDS ends the chase.
+const ESCAPE_DISTANCE: f32 = 200.0;
+const ESCAPE_SECONDS: f32 = 4.0;
+const SIREN_FLASH_SECONDS: f32 = 0.25;
+
+pub struct PolicePlugin;
+
+impl Plugin for PolicePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Heat>()
+            .add_systems(RunSetup, reset_heat)
+            .add_systems(
+                Update,
+                (
+                    (spawn_police, pursue_player).chain().in_set(GameSet::Movement),
+                    (build_heat, check_escape).chain().in_set(GameSet::Cleanup),
+                    flash_siren_lights.in_set(GameSet::Ui),
+                ),
+            );
+    }
+}
+
+pub fn reset_heat(mut heat: ResMut<Heat>) {
+    *heat = Heat::default();
+}
+
+// Near misses and speeding raise the heat. It cools down again once no police
+// are chasing.
+pub fn build_heat(
+    time: Res<Time>,
+    mut heat: ResMut<Heat>,
+    mut near_miss_events: EventReader<NearMiss>,
+    player_query: Query<&Velocity, With<Player>>,
+    police_query: Query<(), With<Police>>,
+) {
+    let dt = time.delta_seconds();
+    heat.value += near_miss_events.iter().count() as f32 * HEAT_PER_NEAR_MISS;
+    if player_query.get_single().is_ok_and(|velocity| velocity.speed > SPEEDING_SPEED) {
+        heat.value += SPEEDING_HEAT * dt;
+    }
+    if police_query.is_empty() {
+        heat.value -= HEAT_DECAY * dt;
+    }
+    heat.value = heat.value.clamp(0.0, MAX_WANTED_LEVEL as f32 + 0.99);
+}
+
+// One police car per wanted level, coming up from below. The first rams, the
+// others try to box the player in from alternate sides.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_police(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut heat: ResMut<Heat>,
+    assets: Res<GameAssets>,
+    geometry: RoadGeometry,
+    police_query: Query<&Police>,
+    player_query: Query<&Transform, With<Player>>,
+    mut rng: ResMut<GameRng>,
+) {
+    heat.spawn_cooldown -= time.delta_seconds();
+    let count = police_query.iter().count();
+    if count >= heat.wanted_level() || heat.spawn_cooldown > 0.0 {
+        return;
+    }
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    heat.spawn_cooldown = POLICE_SPAWN_COOLDOWN;
+
+    let role = if police_query.iter().all(|police| police.role != PoliceRole::Rammer) {
+        PoliceRole::Rammer
+    } else {
+        PoliceRole::Blocker {
+            side: if count.is_multiple_of(2) { 1.0 } else { -1.0 },
+        }
+    };
+    let y = -HALF_HEIGHT - POLICE_TRAIL;
+    let (left, right) = geometry.at(y).bounds();
+    let x = (player_transform.translation.x + rng.0.random_range(-60.0..60.0)).clamp(left, right);
+    let car_type = CarType::Police;
+
+    commands
+        .spawn((
+            SpriteBundle {
+                texture: assets.car(car_type),
+                transform: Transform {
+                    translation: Vec3::new(x, y, 10.0),
+                    scale: Vec3::splat(car_type.sprite_scale()),
+                    ..default()
+                },
+                ..default()
+            },
+            Police { role },
+            car_type,
+            Solid,
+            CollisionBounds {
+                size: car_type.collision_bounds(),
+            },
+            RunScoped,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: Color::RED,
+                        custom_size: Some(Vec2::new(36.0, 6.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, 8.0, 0.1),
+                    ..default()
+                },
+                SirenLight,
+            ));
+        });
+}
+
+// Police gain on the player unless the player outruns them, while steering for
+// the player's rear or for the lane beside them
+pub fn pursue_player(
+    time: Res<Time>,
+    geometry: RoadGeometry,
+    player_query: Query<(&Transform, &Velocity), With<Player>>,
+    mut police_query: Query<(&mut Transform, &Police), Without<Player>>,
+) {
+    let Ok((player_transform, player_velocity)) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation;
+    let dt = time.delta_seconds();
+
+    for (mut transform, police) in police_query.iter_mut() {
+        let closing = (POLICE_SPEED - player_velocity.speed) * dt;
+        transform.translation.y = (transform.translation.y + closing).clamp(-HALF_HEIGHT - POLICE_TRAIL, player_pos.y);
+
+        let shape = geometry.at(transform.translation.y);
+        let (left, right) = shape.bounds();
+        let target_x = match police.role {
+            PoliceRole::Rammer => player_pos.x,
+            PoliceRole::Blocker { side } => {
+                // Take whichever side is still on the road
+                let beside = player_pos.x + side * shape.lane_width;
+                if beside < left || beside > right {
+                    player_pos.x - side * shape.lane_width
+                } else {
+                    beside
+                }
+            }
+        };
+        let step = POLICE_STEER_SPEED * dt;
+        transform.translation.x += (target_x - transform.translation.x).clamp(-step, step);
+        transform.translation.x = transform.translation.x.clamp(left, right);
+    }
+}
+
+// Keeping every police car well behind for long enough loses them and clears
+// the heat
+pub fn check_escape(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut heat: ResMut<Heat>,
+    player_query: Query<&Transform, With<Player>>,
+    police_query: Query<(Entity, &Transform), With<Police>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    if police_query.is_empty() {
+        heat.escape_time = 0.0;
+        return;
+    }
+
+    let losing = police_query
+        .iter()
+        .all(|(_, transform)| player_transform.translation.y - transform.translation.y > ESCAPE_DISTANCE);
+    heat.escape_time = if losing { heat.escape_time + time.delta_seconds() } else { 0.0 };
+
+    if heat.escape_time >= ESCAPE_SECONDS {
+        for (entity, _) in police_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        *heat = Heat::default();
+    }
+}
+
+// Light bars alternate red and blue
+pub fn flash_siren_lights(time: Res<Time>, mut lights: Query<&mut Sprite, With<SirenLight>>) {
+    let red = ((time.elapsed_seconds() / SIREN_FLASH_SECONDS) as u32).is_multiple_of(2);
+    for mut sprite in lights.iter_mut() {
+        sprite.color = if red { Color::RED } else { Color::BLUE };
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use crate::components::{CollisionBounds, Lane, Pickup, Player, PowerUp, RunScoped};
+use crate::game::GameSet;
+use crate::playfield::DESPAWN_Y;
+use crate::resources::{ActiveEffects, GameAssets, GameSpeed};
+use crate::systems::{aabb_collision, check_collision, update_game_speed, RoadGeometry, ROAD_SPEED};
+
+const POWER_UP_SIZE: Vec2 = Vec2::new(32.0, 32.0);
+// Pickups closer than this to the player are pulled in while the magnet is active
+const MAGNET_RANGE: f32 = 250.0;
+const MAGNET_PULL_SPEED: f32 = 600.0;
+
+// The player drove over a power-up
+#[derive(Event)]
+pub struct PowerUpCollected {
+    pub kind: PowerUp,
+    pub position: Vec3,
+}
+
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PowerUpCollected>().add_systems(
+            Update,
+            (
+                tick_power_ups.before(update_game_speed).in_set(GameSet::Progress),
+                move_pickups.in_set(GameSet::Movement),
+                // A shield collected in the same frame as a crash still counts
+                collect_power_ups.before(check_collision).in_set(GameSet::Collision),
+                cleanup_pickups.in_set(GameSet::Cleanup),
+            ),
+        );
+    }
+}
+
+pub fn spawn_power_up(commands: &mut Commands, assets: &GameAssets, kind: PowerUp, lane: usize, position: Vec3) {
+    commands.spawn((
+        SpriteBundle {
+            texture: assets.power_up(kind),
+            sprite: Sprite {
+                custom_size: Some(POWER_UP_SIZE),
+                ..default()
+            },
+            transform: Transform::from_translation(position),
+            ..default()
+        },
+        kind,
+        Pickup,
+        Lane(lane),
+        CollisionBounds { size: POWER_UP_SIZE },
+        RunScoped,
+    ));
+}
+
+pub fn tick_power_ups(time: Res<Time>, mut effects: ResMut<ActiveEffects>) {
+    effects.tick(time.delta_seconds());
+}
+
+// Pickups lie still on the road, so they scroll with it and keep to their lane.
+// The magnet pulls the ones in range straight at the player instead.
+#[allow(clippy::type_complexity)]
+pub fn move_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    effects: Res<ActiveEffects>,
+    geometry: RoadGeometry,
+    player_query: Query<&Transform, With<Player>>,
+    mut pickups: Query<(Entity, &mut Transform, Option<&Lane>), (With<Pickup>, Without<Player>)>,
+) {
+    let dt = time.delta_seconds();
+    let magnet = player_query
+        .get_single()
+        .ok()
+        .filter(|_| effects.is_active(PowerUp::Magnet))
+        .map(|transform| transform.translation.truncate());
+
+    for (entity, mut transform, lane) in pickups.iter_mut() {
+        transform.translation.y -= ROAD_SPEED * game_speed.multiplier * dt;
+
+        if let Some(player_pos) = magnet {
+            let offset = player_pos - transform.translation.truncate();
+            let distance = offset.length();
+            if distance < MAGNET_RANGE {
+                let step = offset.normalize_or_zero() * (MAGNET_PULL_SPEED * dt).min(distance);
+                transform.translation += step.extend(0.0);
+                if lane.is_some() {
+                    commands.entity(entity).remove::<Lane>();
+                }
+                continue;
+            }
+        }
+
+        if let Some(lane) = lane {
+            transform.translation.x = geometry.at(transform.translation.y).lane_center(lane.0);
+        }
+    }
+}
+
+pub fn collect_power_ups(
+    mut commands: Commands,
+    mut effects: ResMut<ActiveEffects>,
+    player_query: Query<(&Transform, &CollisionBounds), With<Player>>,
+    power_up_query: Query<(Entity, &Transform, &CollisionBounds, &PowerUp)>,
+    mut collected_events: EventWriter<PowerUpCollected>,
+) {
+    let Ok((player_transform, player_bounds)) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (entity, transform, bounds, kind) in power_up_query.iter() {
+        if aabb_collision(player_pos, player_bounds.size, transform.translation.truncate(), bounds.size) {
+            effects.activate(*kind);
+            commands.entity(entity).despawn();
+            collected_events.send(PowerUpCollected {
+                kind: *kind,
+                position: transform.translation,
+            });
+        }
+    }
+}
+
+pub fn cleanup_pickups(mut commands: Commands, query: Query<(Entity, &Transform), With<Pickup>>) {
+    for (entity, transform) in query.iter() {
+        if transform.translation.y < DESPAWN_Y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -1,12 +1,221 @@
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use crate::components::RoadLine;
-use crate::resources::GameSpeed;
+use rand::Rng;
+use crate::components::{
+    Road, RoadLine, RoadStrip, RunScoped, Scenery, SceneryKind, Shoulder, WarningSign,
+};
+use crate::game::{reset_run_resources, GameSet, RunSetup};
+use crate::playfield::{
+    DESPAWN_Y, HALF_HEIGHT, PLAYFIELD_SIZE, ROAD_HALF_WIDTH, ROAD_MARGIN, SHOULDER_WIDTH, SPAWN_Y,
+};
+use crate::resources::{GameAssets, GameSpeed, StageDef, Stages};
+use crate::systems::{extend_road_layout, pick_weighted, update_game_speed, LaneEnds, RoadGeometry, StageChanged};
 
+// Scroll speed of the road surface at 1x game speed
+pub const ROAD_SPEED: f32 = 150.0;
+
+// The road textures tile seamlessly top to bottom; they are scaled to the road
+// width keeping their aspect ratio
+const ROAD_TEXTURE_SIZE: Vec2 = Vec2::new(77.0, 367.0);
+const ROAD_TILE_HEIGHT: f32 = ROAD_HALF_WIDTH * 2.0 / ROAD_TEXTURE_SIZE.x * ROAD_TEXTURE_SIZE.y;
+// The road is drawn as thin strips so it can curve. A whole number of strips
+// per tile keeps every strip inside the texture.
+const STRIPS_PER_TILE: usize = 96;
+const STRIP_HEIGHT: f32 = ROAD_TILE_HEIGHT / STRIPS_PER_TILE as f32;
+const STRIP_TEXELS: f32 = ROAD_TEXTURE_SIZE.y / STRIPS_PER_TILE as f32;
+const STRIP_COUNT: usize = (PLAYFIELD_SIZE.y / STRIP_HEIGHT) as usize + 3;
+
+// Guardrail segments sit right outside the shoulder and move with the road
+const BARRIER_OFFSET: f32 = 12.0;
+// Trees and buildings stand taller than the road, so they scroll a little faster
+const VERGE_PARALLAX: f32 = 1.25;
+const VERGE_SPACING: f32 = 160.0;
+// Scenery wraps once fully below the screen; the largest piece is 128 tall
+const SCENERY_MARGIN: f32 = 128.0;
+const SIGN_SIZE: f32 = 40.0;
+
+pub struct RoadPlugin;
+
+impl Plugin for RoadPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<LaneEnds>()
+            .add_systems(Startup, spawn_road)
+            .add_systems(
+                RunSetup,
+                (spawn_lane_lines.after(reset_run_resources), reset_road_stage),
+            )
+            .add_systems(
+                Update,
+                (
+                    // Sends LaneEnds, so it lives with the signs that read them
+                    extend_road_layout.after(update_game_speed).in_set(GameSet::Progress),
+                    (
+                        layout_road_strips,
+                        scroll_road_lines,
+                        scroll_scenery,
+                        spawn_warning_signs,
+                        place_warning_signs,
+                    )
+                        .in_set(GameSet::Movement),
+                    change_road_stage.in_set(GameSet::Cleanup),
+                ),
+            );
+    }
+}
+
+fn rgb((r, g, b): (f32, f32, f32)) -> Color {
+    Color::rgb(r, g, b)
+}
+
+pub fn spawn_road(mut commands: Commands, assets: Res<GameAssets>, stages: Res<Stages>) {
+    let stage = stages.get(0);
+
+    // Verge behind everything
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: rgb(stage.verge_color),
+                custom_size: Some(PLAYFIELD_SIZE),
+                ..default()
+            },
+            ..default()
+        },
+        Road,
+    ));
+
+    // Strips are placed every frame by layout_road_strips
+    for index in 0..STRIP_COUNT {
+        commands.spawn((
+            SpriteBundle {
+                texture: assets.road(&stage.road_texture),
+                sprite: Sprite {
+                    color: rgb(stage.tint),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                ..default()
+            },
+            RoadStrip { index },
+        ));
+
+        // Gravel shoulder, a flat color so only its position changes
+        for side in [-1.0, 1.0] {
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: rgb(stage.shoulder_color),
+                        custom_size: Some(Vec2::new(SHOULDER_WIDTH, STRIP_HEIGHT + 1.0)),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                    ..default()
+                },
+                RoadStrip { index },
+                Shoulder { side },
+            ));
+        }
+    }
+
+    for side in [-1.0, 1.0] {
+        spawn_scenery_column(&mut commands, &assets, stage, side);
+    }
+}
+
+fn spawn_scenery_column(commands: &mut Commands, assets: &GameAssets, stage: &StageDef, side: f32) {
+    let mut rng = rand::rng();
+    let bottom = -HALF_HEIGHT - SCENERY_MARGIN;
+
+    // Continuous guardrail, shown on the stages that have one
+    let barrier_size = SceneryKind::Barrier.size();
+    let barrier_count = ((PLAYFIELD_SIZE.y + 2.0 * SCENERY_MARGIN) / barrier_size.y).ceil() as usize + 1;
+    for i in 0..barrier_count {
+        commands.spawn((
+            SpriteBundle {
+                texture: assets.scenery(SceneryKind::Barrier),
+                sprite: Sprite {
+                    color: rgb(stage.tint),
+                    custom_size: Some(barrier_size),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, bottom + i as f32 * barrier_size.y, 0.5),
+                visibility: guardrail_visibility(stage),
+                ..default()
+            },
+            SceneryKind::Barrier,
+            Scenery {
+                parallax: 1.0,
+                span: barrier_count as f32 * barrier_size.y,
+                side,
+                offset: BARRIER_OFFSET,
+            },
+        ));
+    }
+
+    // The stage's scenery set on the verge
+    let verge_count = ((PLAYFIELD_SIZE.y + 2.0 * SCENERY_MARGIN) / VERGE_SPACING).ceil() as usize + 1;
+    for i in 0..verge_count {
+        let kind = random_verge_kind(stage, &mut rng);
+        let y = bottom + i as f32 * VERGE_SPACING;
+        commands.spawn((
+            SpriteBundle {
+                texture: assets.scenery(kind),
+                sprite: Sprite {
+                    color: rgb(stage.tint),
+                    custom_size: Some(kind.size()),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, y, 5.0),
+                ..default()
+            },
+            kind,
+            Scenery {
+                parallax: VERGE_PARALLAX,
+                span: verge_count as f32 * VERGE_SPACING,
+                side,
+                offset: verge_offset(kind, &mut rng),
+            },
+        ));
+    }
+}
+
+fn guardrail_visibility(stage: &StageDef) -> Visibility {
+    if stage.guardrail {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+fn random_verge_kind(stage: &StageDef, rng: &mut impl Rng) -> SceneryKind {
+    pick_weighted(&stage.scenery, rng).unwrap_or(SceneryKind::Tree)
+}
+
+// Just past the guardrail, partly off screen for the wider pieces
+fn verge_offset(kind: SceneryKind, rng: &mut impl Rng) -> f32 {
+    SceneryKind::Barrier.size().x + kind.size().x / 2.0 + rng.random_range(0.0..16.0)
+}
+
+pub fn spawn_lane_lines(mut commands: Commands, assets: Res<GameAssets>, geometry: RoadGeometry) {
+    spawn_stage_lane_lines(&mut commands, &assets, &geometry);
+}
+
+fn spawn_stage_lane_lines(commands: &mut Commands, assets: &GameAssets, geometry: &RoadGeometry) {
+    let stage = geometry.stage();
+    for boundary in 1..stage.lanes {
+        // The center line is painted on the road texture
+        if stage.center_line && boundary * 2 == stage.lanes {
+            continue;
+        }
+        spawn_road_lines(commands, assets.road_line.clone(), rgb(stage.tint), geometry, boundary, 10);
+    }
+}
 
 pub fn spawn_road_lines(
     commands: &mut Commands,
     texture: Handle<Image>,
-    x_position: f32,
+    color: Color,
+    geometry: &RoadGeometry,
+    boundary: usize,
     count: usize,
 ) {
     let line_spacing = 150.0; // Space between lines
@@ -14,31 +223,241 @@ pub fn spawn_road_lines(
 
     for i in 0..count {
         let y_position = start_y - (i as f32 * line_spacing);
+        let x_position = lane_boundary_x(geometry, y_position, boundary);
 
         commands.spawn((
             SpriteBundle {
                 texture: texture.clone(),
                 sprite: Sprite {
+                    color,
                     custom_size: Some(Vec2::new(10.0, 50.0)), // Thin, short line
                     ..default()
                 },
                 transform: Transform::from_xyz(x_position, y_position, 1.0),
                 ..default()
             },
-            RoadLine { speed: 150.0 },
+            RoadLine {
+                speed: ROAD_SPEED,
+                boundary,
+            },
+            RunScoped,
         ));
     }
 }
 
+fn lane_boundary_x(geometry: &RoadGeometry, y: f32, boundary: usize) -> f32 {
+    let shape = geometry.at(y);
+    shape.left_edge() + boundary as f32 * shape.lane_width
+}
+
+// Everything on screen whose look depends on the stage
+#[derive(SystemParam)]
+#[allow(clippy::type_complexity)]
+pub struct RoadSprites<'w, 's> {
+    verge: Query<'w, 's, &'static mut Sprite, (With<Road>, Without<RoadStrip>)>,
+    strips: Query<'w, 's, (&'static mut Sprite, &'static mut Handle<Image>), (With<RoadStrip>, Without<Shoulder>)>,
+    shoulders: Query<'w, 's, &'static mut Sprite, (With<RoadStrip>, With<Shoulder>)>,
+    scenery: Query<
+        'w,
+        's,
+        (&'static mut Sprite, &'static mut Visibility, &'static SceneryKind),
+        (With<Scenery>, Without<Road>, Without<RoadStrip>),
+    >,
+}
+
+impl RoadSprites<'_, '_> {
+    fn apply(&mut self, stage: &StageDef, assets: &GameAssets) {
+        for mut sprite in self.verge.iter_mut() {
+            sprite.color = rgb(stage.verge_color);
+        }
+        for (mut sprite, mut texture) in self.strips.iter_mut() {
+            sprite.color = rgb(stage.tint);
+            *texture = assets.road(&stage.road_texture);
+        }
+        for mut sprite in self.shoulders.iter_mut() {
+            sprite.color = rgb(stage.shoulder_color);
+        }
+        for (mut sprite, _, _) in self.scenery.iter_mut() {
+            sprite.color = rgb(stage.tint);
+        }
+    }
+
+    // Guardrails normally come and go as they scroll in, see scroll_scenery
+    fn show_guardrail(&mut self, stage: &StageDef) {
+        for (_, mut visibility, kind) in self.scenery.iter_mut() {
+            if *kind == SceneryKind::Barrier {
+                *visibility = guardrail_visibility(stage);
+            }
+        }
+    }
+}
+
+pub fn reset_road_stage(assets: Res<GameAssets>, stages: Res<Stages>, mut road: RoadSprites) {
+    let stage = stages.get(0);
+    road.apply(stage, &assets);
+    road.show_guardrail(stage);
+}
+
+// Restyles the road and redraws the lane lines at each stage checkpoint
+pub fn change_road_stage(
+    mut commands: Commands,
+    mut stage_events: EventReader<StageChanged>,
+    assets: Res<GameAssets>,
+    geometry: RoadGeometry,
+    lines_query: Query<Entity, With<RoadLine>>,
+    mut road: RoadSprites,
+) {
+    if stage_events.iter().count() == 0 {
+        return;
+    }
+
+    road.apply(geometry.stage(), &assets);
+    for entity in lines_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    spawn_stage_lane_lines(&mut commands, &assets, &geometry);
+}
+
+// Lines the strips up with the road under them. Strip i always covers the
+// same stretch of the run, so its slice of the texture only depends on where
+// it is along the road.
+pub fn layout_road_strips(
+    geometry: RoadGeometry,
+    game_speed: Res<GameSpeed>,
+    mut strips_query: Query<(&RoadStrip, Option<&Shoulder>, &mut Transform, &mut Sprite)>,
+) {
+    let first = ((game_speed.distance - HALF_HEIGHT) / STRIP_HEIGHT).floor() as i64 - 1;
+    for (strip, shoulder, mut transform, mut sprite) in strips_query.iter_mut() {
+        let run_strip = first + strip.index as i64;
+        let y = (run_strip as f32 + 0.5) * STRIP_HEIGHT - game_speed.distance;
+        let shape = geometry.at(y);
+        let road_half_width = shape.half_width() + ROAD_MARGIN;
+
+        transform.translation.y = y;
+        match shoulder {
+            Some(shoulder) => {
+                transform.translation.x = shape.center + shoulder.side * (road_half_width + SHOULDER_WIDTH / 2.0);
+            }
+            None => {
+                transform.translation.x = shape.center;
+                // The extra unit of height hides seams between strips
+                sprite.custom_size = Some(Vec2::new(road_half_width * 2.0, STRIP_HEIGHT + 1.0));
+                // Further along the road is higher up the texture
+                let from_top = STRIPS_PER_TILE - 1 - run_strip.rem_euclid(STRIPS_PER_TILE as i64) as usize;
+                let top = from_top as f32 * STRIP_TEXELS;
+                sprite.rect = Some(Rect::new(0.0, top, ROAD_TEXTURE_SIZE.x, top + STRIP_TEXELS));
+            }
+        }
+    }
+}
+
 pub fn scroll_road_lines(
-    mut lines_query: Query<(&mut Transform, &RoadLine)>,
+    mut lines_query: Query<(&mut Transform, &mut Visibility, &RoadLine)>,
     time: Res<Time>,
     game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
 ) {
-    for (mut transform, line) in lines_query.iter_mut() {
+    for (mut transform, mut visibility, line) in lines_query.iter_mut() {
         transform.translation.y -= line.speed * game_speed.multiplier * time.delta_seconds();
-        if transform.translation.y < -350.0 {
-            transform.translation.y = 350.0;
+        if transform.translation.y < DESPAWN_Y {
+            transform.translation.y = SPAWN_Y;
+        }
+
+        // Follow the curve and fade out between lanes that are closing
+        let y = transform.translation.y;
+        let shape = geometry.at(y);
+        transform.translation.x = lane_boundary_x(&geometry, y, line.boundary);
+        let slope = geometry.at(y + 1.0).center - shape.center;
+        transform.rotation = Quat::from_rotation_z(-slope.atan());
+        *visibility = if (line.boundary as f32) < shape.lanes - 0.5 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+// Wraps scenery back to the top like the road lines. Verge pieces are picked
+// again from the current stage's set so the verge does not visibly repeat, and
+// the guardrail scrolls in or out with the stage.
+#[allow(clippy::type_complexity)]
+pub fn scroll_scenery(
+    mut scenery_query: Query<(
+        &mut Transform,
+        &mut Sprite,
+        &mut Handle<Image>,
+        &mut Visibility,
+        &mut SceneryKind,
+        &mut Scenery,
+    )>,
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    assets: Res<GameAssets>,
+    geometry: RoadGeometry,
+) {
+    let mut rng = rand::rng();
+    let stage = geometry.stage();
+    for (mut transform, mut sprite, mut texture, mut visibility, mut kind, mut scenery) in scenery_query.iter_mut() {
+        transform.translation.y -= ROAD_SPEED * scenery.parallax * game_speed.multiplier * time.delta_seconds();
+        if transform.translation.y < -HALF_HEIGHT - SCENERY_MARGIN {
+            transform.translation.y += scenery.span;
+            if *kind == SceneryKind::Barrier {
+                *visibility = guardrail_visibility(stage);
+            } else {
+                *kind = random_verge_kind(stage, &mut rng);
+                *texture = assets.scenery(*kind);
+                sprite.custom_size = Some(kind.size());
+                scenery.offset = verge_offset(*kind, &mut rng);
+            }
         }
+
+        let shape = geometry.at(transform.translation.y);
+        let shoulder_edge = shape.half_width() + ROAD_MARGIN + SHOULDER_WIDTH;
+        transform.translation.x = shape.center + scenery.side * (shoulder_edge + scenery.offset);
+    }
+}
+
+pub fn spawn_warning_signs(
+    mut commands: Commands,
+    mut lane_events: EventReader<LaneEnds>,
+    assets: Res<GameAssets>,
+) {
+    for lane_ends in lane_events.iter() {
+        commands.spawn((
+            SpriteBundle {
+                texture: assets.lane_ends_sign.clone(),
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(SIGN_SIZE)),
+                    ..default()
+                },
+                // Placed by place_warning_signs before it is drawn
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            WarningSign {
+                distance: lane_ends.distance,
+            },
+            RunScoped,
+        ));
+    }
+}
+
+// Signs stand on the right shoulder, where the lane closes
+pub fn place_warning_signs(
+    mut commands: Commands,
+    mut signs_query: Query<(Entity, &WarningSign, &mut Transform, &mut Visibility)>,
+    game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
+) {
+    for (entity, sign, mut transform, mut visibility) in signs_query.iter_mut() {
+        let y = sign.distance - game_speed.distance;
+        if y < DESPAWN_Y - SIGN_SIZE {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let shape = geometry.at(y);
+        transform.translation = Vec3::new(shape.right_edge() + ROAD_MARGIN + SHOULDER_WIDTH / 2.0, y, 6.0);
+        *visibility = Visibility::Inherited;
     }
 }
@@ -0,0 +1,207 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::VecDeque;
+use crate::playfield::{HALF_WIDTH, LANES_HALF_WIDTH, PLAYFIELD_SIZE, ROAD_MARGIN};
+use crate::resources::{CurrentStage, GameRng, GameSpeed, StageDef, Stages};
+
+// Lane warning signs stand this far before a lane starts to close
+pub const LANE_WARNING_DISTANCE: f32 = 600.0;
+// How far past the screen the layout is generated, so warnings can be placed
+// before they scroll into view
+const LOOKAHEAD: f32 = PLAYFIELD_SIZE.y + LANE_WARNING_DISTANCE + 200.0;
+// Length over which a lane closes or opens again
+const TAPER_LENGTH: f32 = 300.0;
+// Keeps every lane on screen through the curves
+const MAX_CENTER_OFFSET: f32 = HALF_WIDTH - LANES_HALF_WIDTH - 20.0;
+// Every run starts on a straight with all lanes open
+const FIRST_SEGMENT_LENGTH: f32 = 1500.0;
+const MERGE_CHANCE: f64 = 0.3;
+const STRAIGHT_CHANCE: f64 = 0.4;
+
+// A lane will close `LANE_WARNING_DISTANCE` after `distance`
+#[derive(Event)]
+pub struct LaneEnds {
+    pub distance: f32,
+}
+
+// A stretch of road that eases from one center offset to another, with some
+// lanes closed along the middle of it. Distances are in world units along the run.
+#[derive(Clone, Copy)]
+pub struct RoadSegment {
+    pub start: f32,
+    pub length: f32,
+    pub center_from: f32,
+    pub center_to: f32,
+    pub closed_lanes: usize,
+}
+
+impl RoadSegment {
+    pub fn end(&self) -> f32 {
+        self.start + self.length
+    }
+
+    fn center_at(&self, distance: f32) -> f32 {
+        let t = ((distance - self.start) / self.length).clamp(0.0, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        self.center_from + (self.center_to - self.center_from) * eased
+    }
+
+    // Closed lanes taper in at the start of the segment and out at its end
+    fn closed_at(&self, distance: f32) -> f32 {
+        let taper_in = ((distance - self.start) / TAPER_LENGTH).clamp(0.0, 1.0);
+        let taper_out = ((self.end() - distance) / TAPER_LENGTH).clamp(0.0, 1.0);
+        self.closed_lanes as f32 * taper_in.min(taper_out)
+    }
+}
+
+// Cross-section of the road at one point
+#[derive(Clone, Copy, Debug)]
+pub struct RoadShape {
+    pub center: f32,
+    // Open lanes, fractional while a lane is tapering
+    pub lanes: f32,
+    pub lane_width: f32,
+}
+
+impl RoadShape {
+    pub fn half_width(&self) -> f32 {
+        self.lanes * self.lane_width / 2.0
+    }
+
+    pub fn left_edge(&self) -> f32 {
+        self.center - self.half_width()
+    }
+
+    pub fn right_edge(&self) -> f32 {
+        self.center + self.half_width()
+    }
+
+    // Lanes that are fully open
+    pub fn open_lanes(&self) -> usize {
+        ((self.lanes + 0.01).floor() as usize).max(1)
+    }
+
+    // Center of a lane counted from the left. A closing lane is squeezed into
+    // the one next to it.
+    pub fn lane_center(&self, lane: usize) -> f32 {
+        let x = self.left_edge() + self.lane_width * (lane as f32 + 0.5);
+        x.min(self.right_edge() - self.lane_width / 2.0)
+    }
+
+    // Player's x limits, letting the car run onto the painted shoulder
+    pub fn bounds(&self) -> (f32, f32) {
+        (self.left_edge() - ROAD_MARGIN, self.right_edge() + ROAD_MARGIN)
+    }
+}
+
+// The road from just below the screen to some way past its top, generated as
+// the run goes on
+#[derive(Resource, Default)]
+pub struct RoadLayout {
+    segments: VecDeque<RoadSegment>,
+}
+
+impl RoadLayout {
+    pub fn reset(&mut self) {
+        self.segments.clear();
+        self.segments.push_back(RoadSegment {
+            start: 0.0,
+            length: FIRST_SEGMENT_LENGTH,
+            center_from: 0.0,
+            center_to: 0.0,
+            closed_lanes: 0,
+        });
+    }
+
+    pub fn end(&self) -> f32 {
+        self.segments.back().map_or(0.0, RoadSegment::end)
+    }
+
+    pub fn shape_at(&self, distance: f32, stage: &StageDef) -> RoadShape {
+        let (center, closed) = match self.segments.iter().find(|segment| distance < segment.end()) {
+            Some(segment) => (segment.center_at(distance), segment.closed_at(distance)),
+            None => (self.segments.back().map_or(0.0, |segment| segment.center_to), 0.0),
+        };
+        RoadShape {
+            center,
+            lanes: stage.lanes as f32 - closed.min((stage.lanes - 1) as f32),
+            lane_width: 2.0 * LANES_HALF_WIDTH / stage.lanes as f32,
+        }
+    }
+
+    fn next_segment(&self, stage: &StageDef, rng: &mut impl Rng) -> RoadSegment {
+        let previous = self.segments.back();
+        let center_from = previous.map_or(0.0, |segment| segment.center_to);
+        let max_offset = stage.curve.min(MAX_CENTER_OFFSET);
+        let center_to = if rng.random_bool(STRAIGHT_CHANCE) {
+            center_from
+        } else {
+            rng.random_range(-max_offset..=max_offset)
+        };
+        // Never two merges in a row, and never down to a single lane
+        let can_merge = stage.merges && stage.lanes > 2 && previous.is_none_or(|segment| segment.closed_lanes == 0);
+        let closed_lanes = if can_merge && rng.random_bool(MERGE_CHANCE) { 1 } else { 0 };
+
+        RoadSegment {
+            start: self.end(),
+            length: rng.random_range(1200.0..2400.0),
+            center_from,
+            center_to,
+            closed_lanes,
+        }
+    }
+}
+
+// Road shape lookups by screen y for the current distance and stage
+#[derive(SystemParam)]
+pub struct RoadGeometry<'w> {
+    layout: Res<'w, RoadLayout>,
+    stages: Res<'w, Stages>,
+    current_stage: Res<'w, CurrentStage>,
+    game_speed: Res<'w, GameSpeed>,
+}
+
+impl RoadGeometry<'_> {
+    pub fn at(&self, y: f32) -> RoadShape {
+        self.layout.shape_at(self.distance_at(y), self.stage())
+    }
+
+    // Distance along the road of the point at screen y
+    pub fn distance_at(&self, y: f32) -> f32 {
+        self.game_speed.distance + y
+    }
+
+    pub fn stage(&self) -> &StageDef {
+        self.stages.get(self.current_stage.0)
+    }
+}
+
+pub fn extend_road_layout(
+    game_speed: Res<GameSpeed>,
+    stages: Res<Stages>,
+    current_stage: Res<CurrentStage>,
+    mut layout: ResMut<RoadLayout>,
+    mut rng: ResMut<GameRng>,
+    mut lane_events: EventWriter<LaneEnds>,
+) {
+    let stage = stages.get(current_stage.0);
+    while layout.end() < game_speed.distance + LOOKAHEAD {
+        let segment = layout.next_segment(stage, &mut rng.0);
+        if segment.closed_lanes > 0 {
+            lane_events.send(LaneEnds {
+                distance: segment.start - LANE_WARNING_DISTANCE,
+            });
+        }
+        layout.segments.push_back(segment);
+    }
+
+    // Forget segments once they are well below the screen
+    while layout
+        .segments
+        .front()
+        .is_some_and(|segment| segment.end() < game_speed.distance - PLAYFIELD_SIZE.y)
+    {
+        layout.segments.pop_front();
+    }
+}
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use rand::seq::IndexedRandom;
+use rand::Rng;
+use crate::game::GameSet;
+use crate::playfield::UNITS_PER_METER;
+use crate::resources::{CurrentStage, GameSpeed, Stages};
+use crate::systems::{extend_road_layout, update_game_speed};
+
+// The run has reached a new stage
+#[derive(Event)]
+pub struct StageChanged {
+    pub index: usize,
+}
+
+pub struct StagePlugin;
+
+impl Plugin for StagePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<StageChanged>().add_systems(
+            Update,
+            advance_stage
+                .after(update_game_speed)
+                .before(extend_road_layout)
+                .in_set(GameSet::Progress),
+        );
+    }
+}
+
+// Picks one of the options, each as likely as its weight
+pub fn pick_weighted<T: Copy>(options: &[(T, u32)], rng: &mut impl Rng) -> Option<T> {
+    options.choose_weighted(rng, |option| option.1).ok().map(|option| option.0)
+}
+
+pub fn advance_stage(
+    game_speed: Res<GameSpeed>,
+    stages: Res<Stages>,
+    mut current_stage: ResMut<CurrentStage>,
+    mut stage_events: EventWriter<StageChanged>,
+) {
+    let index = stages.index_at(game_speed.distance / UNITS_PER_METER);
+    if index != current_stage.0 {
+        current_stage.0 = index;
+        stage_events.send(StageChanged { index });
+    }
+}
@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use crate::components::{Player, Velocity};
+use crate::game::GameSet;
+use crate::playfield::UNITS_PER_METER;
+use crate::resources::{GameMode, GameOver, GameSpeed, LifetimeStats};
+use crate::systems::{Crash, NearMiss};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LifetimeStats>()
+            .add_systems(Update, record_lifetime_stats.in_set(GameSet::Ui));
+    }
+}
+
+// Counts near misses, crashes and top speed as they happen, and adds the run's
+// distance and time once it ends. Time trials end on the clock, so like the
+// personal best they are left out of the run count and survival time.
+pub fn record_lifetime_stats(
+    mode: Res<GameMode>,
+    game_over: Res<GameOver>,
+    game_speed: Res<GameSpeed>,
+    player_query: Query<&Velocity, With<Player>>,
+    mut near_miss_events: EventReader<NearMiss>,
+    mut crash_events: EventReader<Crash>,
+    mut lifetime: ResMut<LifetimeStats>,
+) {
+    let near_misses = near_miss_events.iter().count() as u32;
+    if near_misses > 0 {
+        lifetime.near_misses += near_misses;
+    }
+    for crash in crash_events.iter() {
+        match crash.car_type {
+            Some(car_type) => *lifetime.crashes.entry(car_type).or_default() += 1,
+            None => lifetime.barrier_crashes += 1,
+        }
+    }
+    if let Ok(velocity) = player_query.get_single() {
+        if velocity.speed > lifetime.best_speed {
+            lifetime.best_speed = velocity.speed;
+        }
+    }
+
+    if game_over.is_changed() && game_over.0 {
+        lifetime.distance += game_speed.distance / UNITS_PER_METER;
+        if *mode == GameMode::Endless {
+            lifetime.runs += 1;
+            lifetime.time += game_speed.time_elapsed;
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use crate::components::{Checkpoint, Player, RunScoped};
+use crate::game::{GameSet, RunSetup};
+use crate::playfield::{DESPAWN_Y, SPAWN_Y, UNITS_PER_METER};
+use crate::resources::{GameMode, GameOver, GameSpeed, TrialRecord};
+use crate::systems::{update_game_speed, RoadGeometry};
+
+// Course length in meters, split evenly between checkpoints with the finish last
+pub const COURSE_LENGTH: f32 = 6000.0;
+pub const CHECKPOINTS: usize = 4;
+// Seconds on the clock at the start, and added at each checkpoint before the
+// finish. Each leg takes about 11 s flat out in the starting car, so the clock
+// runs out on anyone who spends long off the throttle.
+pub const TRIAL_START_TIME: f32 = 15.0;
+pub const CHECKPOINT_BONUS: f32 = 12.0;
+const CHECKPOINT_LINE_HEIGHT: f32 = 12.0;
+
+// Clock and splits of the time trial being driven
+#[derive(Resource)]
+pub struct TimeTrial {
+    pub time_left: f32,
+    // Run time at each checkpoint passed so far
+    pub splits: Vec<f32>,
+    // Each split minus the best run's at the same checkpoint, when there is one
+    pub deltas: Vec<Option<f32>>,
+    pub finished: bool,
+    // The finish beat the best time
+    pub new_record: bool,
+    lines_spawned: usize,
+}
+
+impl Default for TimeTrial {
+    fn default() -> Self {
+        Self {
+            time_left: TRIAL_START_TIME,
+            splits: Vec::new(),
+            deltas: Vec::new(),
+            finished: false,
+            new_record: false,
+            lines_spawned: 0,
+        }
+    }
+}
+
+impl TimeTrial {
+    // Distance along the road of a checkpoint, in world units
+    pub fn checkpoint_distance(index: usize) -> f32 {
+        COURSE_LENGTH / CHECKPOINTS as f32 * (index + 1) as f32 * UNITS_PER_METER
+    }
+
+    pub fn next_checkpoint(&self) -> usize {
+        self.splits.len()
+    }
+
+    pub fn timed_out(&self) -> bool {
+        !self.finished && self.time_left <= 0.0
+    }
+}
+
+// The player crossed a checkpoint, or the finish if it is the last one
+#[derive(Event)]
+pub struct CheckpointReached {
+    pub index: usize,
+    pub split: f32,
+    // Ahead of the best run when negative, if there is one
+    pub delta: Option<f32>,
+}
+
+impl CheckpointReached {
+    pub fn is_finish(&self) -> bool {
+        self.index + 1 == CHECKPOINTS
+    }
+}
+
+pub struct TimeTrialPlugin;
+
+impl Plugin for TimeTrialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeTrial>()
+            .init_resource::<TrialRecord>()
+            .add_event::<CheckpointReached>()
+            .add_systems(RunSetup, reset_time_trial)
+            .add_systems(
+                Update,
+                (
+                    run_trial_clock.after(update_game_speed).in_set(GameSet::Progress),
+                    spawn_checkpoint_lines.in_set(GameSet::Movement),
+                    cleanup_checkpoint_lines.in_set(GameSet::Cleanup),
+                )
+                    .run_if(in_time_trial),
+            );
+    }
+}
+
+pub fn in_time_trial(mode: Res<GameMode>) -> bool {
+    *mode == GameMode::TimeTrial
+}
+
+pub fn reset_time_trial(mut trial: ResMut<TimeTrial>) {
+    *trial = TimeTrial::default();
+}
+
+// Counts the clock down, records a split at each checkpoint and ends the run at
+// the finish or when time runs out
+#[allow(clippy::too_many_arguments)]
+pub fn run_trial_clock(
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
+    player_query: Query<&Transform, With<Player>>,
+    mut trial: ResMut<TimeTrial>,
+    mut record: ResMut<TrialRecord>,
+    mut game_over: ResMut<GameOver>,
+    mut checkpoint_events: EventWriter<CheckpointReached>,
+) {
+    trial.time_left -= time.delta_seconds();
+
+    if let Ok(transform) = player_query.get_single() {
+        let distance = geometry.distance_at(transform.translation.y);
+        while trial.next_checkpoint() < CHECKPOINTS
+            && distance >= TimeTrial::checkpoint_distance(trial.next_checkpoint())
+        {
+            let index = trial.next_checkpoint();
+            let split = game_speed.time_elapsed;
+            let delta = record.best_split(index).map(|best| split - best);
+            trial.splits.push(split);
+            trial.deltas.push(delta);
+            checkpoint_events.send(CheckpointReached { index, split, delta });
+
+            if index + 1 == CHECKPOINTS {
+                trial.finished = true;
+                trial.new_record = record.submit(&trial.splits);
+                game_over.0 = true;
+                return;
+            }
+            trial.time_left += CHECKPOINT_BONUS;
+        }
+    }
+
+    if trial.timed_out() {
+        trial.time_left = 0.0;
+        game_over.0 = true;
+    }
+}
+
+// Lays a line across the road as each checkpoint comes up to the screen. Lines
+// are placed by distance every frame, so they stay in step with the road.
+pub fn spawn_checkpoint_lines(
+    mut commands: Commands,
+    game_speed: Res<GameSpeed>,
+    geometry: RoadGeometry,
+    mut trial: ResMut<TimeTrial>,
+    mut line_query: Query<(&Checkpoint, &mut Transform, &mut Sprite)>,
+) {
+    while trial.lines_spawned < CHECKPOINTS
+        && TimeTrial::checkpoint_distance(trial.lines_spawned) <= geometry.distance_at(SPAWN_Y)
+    {
+        let index = trial.lines_spawned;
+        let color = if index + 1 == CHECKPOINTS {
+            Color::WHITE
+        } else {
+            Color::rgba(0.2, 0.9, 1.0, 0.8)
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite { color, ..default() },
+                transform: Transform::from_xyz(0.0, SPAWN_Y, 1.5),
+                ..default()
+            },
+            Checkpoint { index },
+            RunScoped,
+        ));
+        trial.lines_spawned += 1;
+    }
+
+    for (checkpoint, mut transform, mut sprite) in line_query.iter_mut() {
+        let y = TimeTrial::checkpoint_distance(checkpoint.index) - game_speed.distance;
+        let shape = geometry.at(y);
+        transform.translation.x = shape.center;
+        transform.translation.y = y;
+        sprite.custom_size = Some(Vec2::new(shape.half_width() * 2.0, CHECKPOINT_LINE_HEIGHT));
+    }
+}
+
+pub fn cleanup_checkpoint_lines(mut commands: Commands, query: Query<(Entity, &Transform), With<Checkpoint>>) {
+    for (entity, transform) in query.iter() {
+        if transform.translation.y < DESPAWN_Y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use crate::game::{GameSet, RunSetup};
+use crate::resources::{Atmosphere, CurrentStage, Stages};
+use crate::systems::advance_stage;
+
+// Daylight change per second while moving to a new time of day
+const DAYLIGHT_RATE: f32 = 0.2;
+// Weather intensity change per second as weather sets in or clears
+const WEATHER_RATE: f32 = 0.3;
+
+pub struct WeatherPlugin;
+
+impl Plugin for WeatherPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Atmosphere>()
+            .add_systems(RunSetup, reset_atmosphere)
+            .add_systems(
+                Update,
+                update_atmosphere.after(advance_stage).in_set(GameSet::Progress),
+            );
+    }
+}
+
+// A run starts in its first stage's light and weather
+pub fn reset_atmosphere(stages: Res<Stages>, mut atmosphere: ResMut<Atmosphere>) {
+    let stage = stages.get(0);
+    *atmosphere = Atmosphere {
+        daylight: stage.time_of_day.daylight(),
+        weather: stage.weather,
+        intensity: 1.0,
+    };
+}
+
+// Eases the light toward the stage's time of day. Different weather clears up
+// completely before the stage's weather sets in.
+pub fn update_atmosphere(
+    time: Res<Time>,
+    stages: Res<Stages>,
+    current_stage: Res<CurrentStage>,
+    mut atmosphere: ResMut<Atmosphere>,
+) {
+    let stage = stages.get(current_stage.0);
+    let dt = time.delta_seconds();
+
+    let target_daylight = stage.time_of_day.daylight();
+    let step = DAYLIGHT_RATE * dt;
+    atmosphere.daylight += (target_daylight - atmosphere.daylight).clamp(-step, step);
+
+    if atmosphere.weather == stage.weather {
+        atmosphere.intensity = (atmosphere.intensity + WEATHER_RATE * dt).min(1.0);
+    } else {
+        atmosphere.intensity -= WEATHER_RATE * dt;
+        if atmosphere.intensity <= 0.0 {
+            atmosphere.intensity = 0.0;
+            atmosphere.weather = stage.weather;
+        }
+    }
+}
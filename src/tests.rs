@@ -0,0 +1,904 @@
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use std::time::Duration;
+use crate::components::{
+    Armor, CarType, Coin, CollisionBounds, Enemy, Explosion, Hazard, LateralVelocity, Player, Police, PoliceRole, PowerUp, RoadLine,
+    RunScoped, Scenery, Skid, Solid, Spent, Velocity,
+};
+use crate::achievements::AchievementsMenu;
+use crate::game::{start_run, CorePlugin, GamePlugin, GameSet};
+use crate::garage::{navigate_garage, GarageMenu};
+use crate::menu::open_main_menu;
+use crate::playfield::{DESPAWN_Y, HALF_HEIGHT, SPAWN_Y, UNITS_PER_METER};
+use crate::stats::StatsMenu;
+use crate::systems::{
+    AchievementRun, HazardSpawnTimer, RoadLayout, TimeTrial, TrafficPlugin, CHECKPOINTS, CHECKPOINT_BONUS, TRIAL_START_TIME,
+};
+use crate::resources::{
+    Achievement, Achievements, ActiveEffects, Atmosphere, CurrentStage, Difficulty, EnemySpawnTimer, GameMode, GameOver, GameSpeed,
+    Heat, InMenu, LifetimeStats, Mission, MissionKind, Missions, OncomingTime, PlayerInput, Profile, RunCoins, Score, Settings, Stages,
+    TrialRecord, Upgrade, UpgradeTiers, Weather,
+};
+use crate::ui::{restart_game, UiClick};
+
+const FRAME: f32 = 1.0 / 60.0;
+
+// Gameplay app with a fixed frame time. The first update runs Startup and
+// has a zero delta, so every test starts from a spawned playfield.
+fn test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, GamePlugin::default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(FRAME)));
+    app.update();
+    app
+}
+
+fn spawn_enemy(app: &mut App, position: Vec3) -> Entity {
+    let car_type = CarType::TealSedan;
+    app.world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(position)),
+            Enemy,
+            Velocity { speed: 200.0 },
+            car_type,
+            CollisionBounds {
+                size: car_type.collision_bounds(),
+            },
+            RunScoped,
+        ))
+        .id()
+}
+
+fn player_translation(app: &mut App) -> Vec3 {
+    app.world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(&app.world)
+        .translation
+}
+
+#[test]
+fn enemies_below_screen_are_despawned() {
+    let mut app = test_app();
+    let below = spawn_enemy(&mut app, Vec3::new(300.0, DESPAWN_Y - 1.0, 10.0));
+    let on_screen = spawn_enemy(&mut app, Vec3::new(300.0, 0.0, 10.0));
+
+    app.update();
+
+    assert!(app.world.get_entity(below).is_none());
+    assert!(app.world.get_entity(on_screen).is_some());
+}
+
+#[test]
+fn road_lines_wrap_to_top() {
+    let mut app = test_app();
+    let line = app
+        .world
+        .query_filtered::<Entity, With<RoadLine>>()
+        .iter(&app.world)
+        .next()
+        .unwrap();
+    app.world.get_mut::<Transform>(line).unwrap().translation.y = DESPAWN_Y + 1.0;
+
+    app.update();
+
+    assert_eq!(app.world.get::<Transform>(line).unwrap().translation.y, SPAWN_Y);
+}
+
+#[test]
+fn scenery_wraps_above_the_screen() {
+    let mut app = test_app();
+    let (piece, span) = app
+        .world
+        .query::<(Entity, &Scenery)>()
+        .iter(&app.world)
+        .map(|(entity, scenery)| (entity, scenery.span))
+        .next()
+        .unwrap();
+    app.world.get_mut::<Transform>(piece).unwrap().translation.y = -HALF_HEIGHT - 500.0;
+
+    app.update();
+
+    let y = app.world.get::<Transform>(piece).unwrap().translation.y;
+    assert!(y > -HALF_HEIGHT - 500.0 + span - 10.0);
+}
+
+#[test]
+fn collision_with_enemy_ends_game() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+
+    app.update();
+
+    assert!(app.world.resource::<GameOver>().0);
+}
+
+#[test]
+fn shield_absorbs_one_hit() {
+    let mut app = test_app();
+    app.world.resource_mut::<ActiveEffects>().activate(PowerUp::Shield);
+    let player_pos = player_translation(&mut app);
+    let first = spawn_enemy(&mut app, player_pos);
+
+    app.update();
+
+    assert!(!app.world.resource::<GameOver>().0);
+    assert!(app.world.get_entity(first).is_none());
+    assert!(!app.world.resource::<ActiveEffects>().is_active(PowerUp::Shield));
+
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+    app.update();
+
+    assert!(app.world.resource::<GameOver>().0);
+}
+
+#[test]
+fn collected_slow_mo_lowers_the_speed_multiplier() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    let pickup = app
+        .world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(player_pos)),
+            PowerUp::SlowMo,
+            CollisionBounds {
+                size: Vec2::splat(32.0),
+            },
+        ))
+        .id();
+
+    app.update();
+    assert!(app.world.get_entity(pickup).is_none());
+    assert!(app.world.resource::<ActiveEffects>().is_active(PowerUp::SlowMo));
+
+    app.update();
+    assert!(app.world.resource::<GameSpeed>().multiplier < 1.0);
+}
+
+#[test]
+fn coins_are_picked_up() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(player_pos)),
+        Coin,
+        CollisionBounds {
+            size: Vec2::splat(24.0),
+        },
+    ));
+
+    app.update();
+
+    assert_eq!(app.world.resource::<RunCoins>().0, 1);
+    assert_eq!(app.world.query::<&Coin>().iter(&app.world).count(), 0);
+}
+
+#[test]
+fn buying_a_car_spends_coins() {
+    let mut profile = Profile {
+        coins: CarType::RedSportscar.price() - 1,
+        ..default()
+    };
+    assert!(!profile.buy(CarType::RedSportscar));
+
+    profile.coins += 1;
+    assert!(profile.buy(CarType::RedSportscar));
+    assert_eq!(profile.coins, 0);
+    assert!(profile.owns(CarType::RedSportscar));
+}
+
+fn missions(kinds: [MissionKind; 3]) -> Missions {
+    Missions {
+        active: kinds.into_iter().map(Mission::new).collect(),
+    }
+}
+
+#[test]
+fn finishing_a_mission_pays_out_and_replaces_it() {
+    let mut app = test_app();
+    app.insert_resource(missions([
+        MissionKind::CollectCoins { count: 1 },
+        MissionKind::NearMisses { count: 10 },
+        MissionKind::Survive { seconds: 90 },
+    ]));
+    let player_pos = player_translation(&mut app);
+    app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(player_pos)),
+        Coin,
+        CollisionBounds {
+            size: Vec2::splat(24.0),
+        },
+    ));
+
+    app.update();
+
+    let reward = MissionKind::CollectCoins { count: 1 }.reward();
+    assert_eq!(app.world.resource::<Profile>().coins, reward);
+    let active = &app.world.resource::<Missions>().active;
+    assert_eq!(active.len(), 3);
+    assert!(active.iter().all(|mission| !mission.is_complete()));
+}
+
+#[test]
+fn missions_without_progress_are_left_unchanged() {
+    let mut app = test_app();
+    app.insert_resource(missions([
+        MissionKind::NearMisses { count: 10 },
+        MissionKind::CollectCoins { count: 20 },
+        MissionKind::CollectPowerUps { kind: PowerUp::Shield, count: 3 },
+    ]));
+    app.update();
+    let last_changed = app.world.resource_mut::<Missions>().last_changed();
+
+    app.update();
+
+    assert_eq!(app.world.resource_mut::<Missions>().last_changed(), last_changed);
+}
+
+#[test]
+fn run_missions_start_over_but_counts_carry_on() {
+    let mut app = test_app();
+    app.insert_resource(missions([
+        MissionKind::NearMisses { count: 10 },
+        MissionKind::Survive { seconds: 90 },
+        MissionKind::CleanDistance { meters: 5000 },
+    ]));
+    app.world.resource_mut::<Missions>().active[0].progress = 4.0;
+
+    for _ in 0..30 {
+        app.update();
+    }
+    let active = &app.world.resource::<Missions>().active;
+    assert!(active[1].progress > 0.0);
+    assert!(active[2].progress > 0.0);
+
+    start_run(&mut app.world);
+
+    let active = &app.world.resource::<Missions>().active;
+    assert_eq!(active[0].progress, 4.0);
+    assert_eq!(active[1].progress, 0.0);
+    assert_eq!(active[2].progress, 0.0);
+}
+
+#[test]
+fn top_speed_time_counts_the_ramp_not_power_ups() {
+    // Slow-mo at the top of the ramp still counts
+    let mut app = test_app();
+    app.world.resource_mut::<GameSpeed>().time_elapsed = 1000.0;
+    app.world.resource_mut::<ActiveEffects>().activate(PowerUp::SlowMo);
+    app.update();
+    assert!(app.world.resource::<AchievementRun>().time_at_top_speed > 0.0);
+
+    // Nitro partway up the ramp does not, even though it reaches 4x
+    let mut app = test_app();
+    app.world.resource_mut::<GameSpeed>().time_elapsed = 2.0 * Difficulty::default().speed_ramp_time();
+    app.world.resource_mut::<ActiveEffects>().activate(PowerUp::Nitro);
+    app.update();
+    assert_eq!(app.world.resource::<GameSpeed>().multiplier, 4.0);
+    assert_eq!(app.world.resource::<AchievementRun>().time_at_top_speed, 0.0);
+}
+
+#[test]
+fn crashing_straight_away_unlocks_wreck() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+
+    app.update();
+
+    assert!(app.world.resource::<GameOver>().0);
+    assert!(app.world.resource::<Achievements>().is_unlocked(Achievement::Wreck));
+    let lifetime = app.world.resource::<LifetimeStats>();
+    assert_eq!(lifetime.runs, 1);
+    assert!(lifetime.distance > 0.0);
+}
+
+#[test]
+fn lifetime_stats_count_crashes_by_car() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+
+    app.update();
+
+    let lifetime = app.world.resource::<LifetimeStats>();
+    assert_eq!(lifetime.crashes_by_car(), vec![(CarType::TealSedan, 1)]);
+    assert_eq!(lifetime.barrier_crashes, 0);
+    assert!(lifetime.time > 0.0);
+    assert!(lifetime.to_csv().contains("crashes_TealSedan,1\n"));
+}
+
+#[test]
+fn time_trials_are_left_out_of_the_run_count() {
+    let mut app = time_trial_app();
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+
+    app.update();
+
+    assert!(app.world.resource::<GameOver>().0);
+    let lifetime = app.world.resource::<LifetimeStats>();
+    assert_eq!(lifetime.runs, 0);
+    assert_eq!(lifetime.time, 0.0);
+    assert_eq!(lifetime.crashes_by_car(), vec![(CarType::TealSedan, 1)]);
+}
+
+#[test]
+fn road_trip_counts_distance_over_all_runs() {
+    let mut app = test_app();
+    app.world.resource_mut::<LifetimeStats>().distance = 9_999.0;
+    assert!(!app.world.resource::<Achievements>().is_unlocked(Achievement::RoadTrip));
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    assert!(app.world.resource::<Achievements>().is_unlocked(Achievement::RoadTrip));
+}
+
+#[test]
+fn upgrades_cost_more_each_tier_and_max_out() {
+    let mut profile = Profile {
+        coins: 10_000,
+        ..default()
+    };
+    let mut spent = Vec::new();
+    while profile.buy_upgrade(CarType::Player, Upgrade::TopSpeed) {
+        spent.push(10_000 - profile.coins - spent.iter().sum::<u32>());
+    }
+
+    assert!(spent.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(profile.upgrades(CarType::Player).next_price(Upgrade::TopSpeed).is_none());
+    assert!(!profile.buy_upgrade(CarType::RedSportscar, Upgrade::TopSpeed));
+}
+
+#[test]
+fn armor_takes_hits_until_it_runs_out() {
+    let mut app = App::new();
+    let upgrades = UpgradeTiers {
+        armor: 1,
+        ..default()
+    };
+    app.add_plugins((MinimalPlugins, GamePlugin::default()))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(FRAME)))
+        .insert_resource(Profile {
+            upgrades: [(CarType::Player, upgrades)].into(),
+            ..default()
+        });
+    app.update();
+
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+    app.update();
+    assert!(!app.world.resource::<GameOver>().0);
+
+    let player_pos = player_translation(&mut app);
+    spawn_enemy(&mut app, player_pos);
+    app.update();
+    assert!(app.world.resource::<GameOver>().0);
+}
+
+#[test]
+fn player_drives_the_selected_car() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, GamePlugin::default()))
+        .insert_resource(Profile {
+            owned_cars: vec![CarType::Player, CarType::GreenHatchback],
+            selected_car: CarType::GreenHatchback,
+            ..default()
+        });
+    app.update();
+
+    let (car_type, bounds) = app
+        .world
+        .query_filtered::<(&CarType, &CollisionBounds), With<Player>>()
+        .single(&app.world);
+    assert_eq!(*car_type, CarType::GreenHatchback);
+    assert_eq!(bounds.size, CarType::GreenHatchback.collision_bounds());
+}
+
+fn spawn_hazard(app: &mut App, kind: Hazard, position: Vec3) -> Entity {
+    let mut entity = app.world.spawn((
+        TransformBundle::from_transform(Transform::from_translation(position)),
+        kind,
+        CollisionBounds { size: kind.size() },
+    ));
+    if kind.is_solid() {
+        entity.insert(Solid);
+    }
+    entity.id()
+}
+
+#[test]
+fn roadworks_barriers_and_cones_are_solid() {
+    for kind in [Hazard::Barrier, Hazard::Cone] {
+        let mut app = test_app();
+        let player_pos = player_translation(&mut app);
+        spawn_hazard(&mut app, kind, player_pos);
+
+        app.update();
+
+        assert!(app.world.resource::<GameOver>().0);
+    }
+}
+
+#[test]
+fn pothole_wrecks_a_car_without_armor() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    let pothole = spawn_hazard(&mut app, Hazard::Pothole, player_pos);
+
+    app.update();
+
+    assert!(app.world.resource::<GameOver>().0);
+    assert!(app.world.get::<Spent>(pothole).is_some());
+}
+
+#[test]
+fn armor_takes_a_pothole() {
+    let mut app = test_app();
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    app.world.entity_mut(player).insert(Armor(1));
+    let player_pos = player_translation(&mut app);
+    let pothole = spawn_hazard(&mut app, Hazard::Pothole, player_pos);
+
+    app.update();
+
+    assert!(!app.world.resource::<GameOver>().0);
+    assert_eq!(app.world.get::<Armor>(player).unwrap().0, 0);
+    assert!(app.world.get::<Spent>(pothole).is_some());
+}
+
+#[test]
+fn oil_slick_takes_away_steering() {
+    let mut app = test_app();
+    let player_pos = player_translation(&mut app);
+    let oil = spawn_hazard(&mut app, Hazard::OilSlick, player_pos);
+
+    app.update();
+
+    assert!(app.world.get::<Spent>(oil).is_some());
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    assert!(app.world.get::<Skid>(player).unwrap().0 > 0.0);
+
+    // Still sliding straight on, whatever the steering says
+    app.world.get_mut::<LateralVelocity>(player).unwrap().0 = 0.0;
+    app.world.resource_mut::<PlayerInput>().steer = 1.0;
+    let x = player_translation(&mut app).x;
+    app.update();
+    assert_eq!(player_translation(&mut app).x, x);
+}
+
+fn police_count(app: &mut App) -> usize {
+    app.world.query::<&Police>().iter(&app.world).count()
+}
+
+#[test]
+fn heat_brings_a_police_car_to_ram_the_player() {
+    let mut app = test_app();
+    app.world.resource_mut::<Heat>().value = 1.5;
+
+    app.update();
+
+    let roles: Vec<PoliceRole> = app.world.query::<&Police>().iter(&app.world).map(|police| police.role).collect();
+    assert_eq!(roles, vec![PoliceRole::Rammer]);
+}
+
+#[test]
+fn restarting_clears_the_police_spawn_cooldown() {
+    let mut app = test_app();
+    app.world.resource_mut::<Heat>().value = 1.5;
+    app.update();
+    assert_eq!(police_count(&mut app), 1);
+
+    // A new run with heat straight away gets its police car at once
+    start_run(&mut app.world);
+    app.world.resource_mut::<Heat>().value = 1.5;
+    app.update();
+
+    assert_eq!(police_count(&mut app), 1);
+}
+
+#[test]
+fn outrunning_the_police_clears_the_heat() {
+    let mut app = test_app();
+    // Nothing else on the road to crash into
+    app.insert_resource(EnemySpawnTimer::new(1000.0))
+        .insert_resource(HazardSpawnTimer(Timer::from_seconds(1000.0, TimerMode::Repeating)));
+    app.world.resource_mut::<Heat>().value = 1.5;
+    app.update();
+    assert_eq!(police_count(&mut app), 1);
+
+    // Faster than the police, but not fast enough to count as speeding
+    let player = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    app.world.get_mut::<Velocity>(player).unwrap().speed = 300.0;
+    for _ in 0..(5.0 / FRAME) as usize {
+        app.update();
+    }
+
+    assert!(!app.world.resource::<GameOver>().0);
+    assert_eq!(police_count(&mut app), 0);
+    assert_eq!(app.world.resource::<Heat>().wanted_level(), 0);
+}
+
+// Score gained in one frame with the player in a lane of the first stage
+fn score_for_one_frame_in_lane(lane: usize) -> f32 {
+    let mut app = test_app();
+    let stage = app.world.resource::<Stages>().get(0).clone();
+    let player_y = player_translation(&mut app).y;
+    let x = app.world.resource::<RoadLayout>().shape_at(player_y, &stage).lane_center(lane);
+    app.world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(&mut app.world)
+        .translation
+        .x = x;
+
+    app.update();
+
+    app.world.resource::<Score>().0
+}
+
+#[test]
+fn driving_in_oncoming_lanes_scores_a_bonus() {
+    let stage = Stages::default().get(0).clone();
+    assert!(stage.oncoming_lanes > 0);
+
+    let oncoming = score_for_one_frame_in_lane(0);
+    let own_side = score_for_one_frame_in_lane(stage.lanes - 1);
+    assert!(oncoming > own_side);
+}
+
+#[test]
+fn one_way_roads_give_no_oncoming_bonus() {
+    let mut app = test_app();
+    let stages = app.world.resource::<Stages>();
+    let index = stages.0.iter().position(|stage| stage.oncoming_lanes == 0).unwrap();
+    let stage = stages.get(index).clone();
+    app.world.resource_mut::<GameSpeed>().distance = stage.distance * UNITS_PER_METER;
+    app.update();
+    assert_eq!(app.world.resource::<CurrentStage>().0, index);
+
+    // As far left as the car can go, on the margin past the road's edge
+    let player_y = player_translation(&mut app).y;
+    let distance = app.world.resource::<GameSpeed>().distance;
+    let (left, _) = app.world.resource::<RoadLayout>().shape_at(distance + player_y, &stage).bounds();
+    app.world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(&mut app.world)
+        .translation
+        .x = left;
+
+    app.update();
+
+    assert_eq!(app.world.resource::<OncomingTime>().0, 0.0);
+}
+
+fn time_trial_app() -> App {
+    let mut app = test_app();
+    app.insert_resource(GameMode::TimeTrial);
+    start_run(&mut app.world);
+    app
+}
+
+// Puts the player just past a checkpoint
+fn drive_to_checkpoint(app: &mut App, index: usize) {
+    let player_y = player_translation(app).y;
+    app.world.resource_mut::<GameSpeed>().distance = TimeTrial::checkpoint_distance(index) - player_y;
+}
+
+// Time trial with nothing on the road to crash into
+fn empty_time_trial_app() -> App {
+    let mut app = time_trial_app();
+    app.insert_resource(EnemySpawnTimer::new(1000.0))
+        .insert_resource(HazardSpawnTimer(Timer::from_seconds(1000.0, TimerMode::Repeating)));
+    app
+}
+
+#[test]
+fn time_trial_ends_when_the_clock_runs_out() {
+    let mut app = empty_time_trial_app();
+
+    // Never touching the throttle, the first checkpoint is out of reach
+    for _ in 0..((TRIAL_START_TIME + 1.0) / FRAME) as usize {
+        app.update();
+    }
+
+    assert!(app.world.resource::<GameOver>().0);
+    let trial = app.world.resource::<TimeTrial>();
+    assert!(trial.timed_out());
+    assert!(trial.splits.is_empty());
+}
+
+#[test]
+fn time_trial_checkpoints_are_reached_flat_out() {
+    let mut app = empty_time_trial_app();
+    app.world.resource_mut::<PlayerInput>().accelerate = true;
+
+    for _ in 0..(TRIAL_START_TIME / FRAME) as usize {
+        app.update();
+    }
+
+    assert!(!app.world.resource::<GameOver>().0);
+    assert_eq!(app.world.resource::<TimeTrial>().splits.len(), 1);
+}
+
+#[test]
+fn easing_off_slows_the_road_in_a_time_trial() {
+    let mut app = empty_time_trial_app();
+    app.world.resource_mut::<PlayerInput>().accelerate = true;
+    for _ in 0..60 {
+        app.update();
+    }
+    let flat_out = app.world.resource::<GameSpeed>().multiplier;
+    assert!(flat_out > 2.0);
+
+    app.world.resource_mut::<PlayerInput>().accelerate = false;
+    for _ in 0..60 {
+        app.update();
+    }
+
+    assert!(app.world.resource::<GameSpeed>().multiplier < flat_out);
+}
+
+#[test]
+fn checkpoints_add_time_and_record_splits() {
+    let mut app = time_trial_app();
+    let time_left = app.world.resource::<TimeTrial>().time_left;
+    drive_to_checkpoint(&mut app, 0);
+
+    app.update();
+
+    let trial = app.world.resource::<TimeTrial>();
+    assert_eq!(trial.splits.len(), 1);
+    assert!(trial.time_left > time_left + CHECKPOINT_BONUS - 1.0);
+    assert!(!app.world.resource::<GameOver>().0);
+}
+
+#[test]
+fn finishing_a_time_trial_keeps_the_best_splits() {
+    let mut app = time_trial_app();
+    app.insert_resource(TrialRecord {
+        best_splits: vec![100.0; CHECKPOINTS],
+    });
+    drive_to_checkpoint(&mut app, CHECKPOINTS - 1);
+
+    app.update();
+
+    assert!(app.world.resource::<GameOver>().0);
+    let trial = app.world.resource::<TimeTrial>();
+    assert!(trial.finished);
+    assert!(trial.new_record);
+    assert!(trial.deltas.iter().all(|delta| delta.unwrap() < 0.0));
+    assert_eq!(app.world.resource::<TrialRecord>().best_splits, trial.splits);
+}
+
+#[test]
+fn endless_runs_have_no_clock() {
+    let mut app = test_app();
+    app.world.resource_mut::<TimeTrial>().time_left = 0.0;
+
+    app.update();
+
+    assert!(!app.world.resource::<GameOver>().0);
+}
+
+#[test]
+fn restart_resets_run() {
+    let mut app = test_app();
+    app.init_resource::<Input<KeyCode>>()
+        .add_event::<UiClick>()
+        .add_systems(Update, restart_game.in_set(GameSet::Ui));
+
+    let player_start = player_translation(&mut app);
+    let line_count = app.world.query::<&RoadLine>().iter(&app.world).count();
+
+    // Steer away from the start, then crash into an enemy leaving an explosion behind
+    app.world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(&mut app.world)
+        .translation
+        .x = -300.0;
+    let player_pos = player_translation(&mut app);
+    let enemy = spawn_enemy(&mut app, player_pos);
+    app.world.resource_mut::<EnemySpawnTimer>().0.tick(Duration::from_secs_f32(0.5));
+    app.update();
+    assert!(app.world.resource::<GameOver>().0);
+    assert_eq!(app.world.query::<&Explosion>().iter(&app.world).count(), 1);
+
+    app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::R);
+    app.update();
+
+    assert!(!app.world.resource::<GameOver>().0);
+    assert!(app.world.get_entity(enemy).is_none());
+    assert_eq!(app.world.query::<&Explosion>().iter(&app.world).count(), 0);
+    assert_eq!(app.world.query::<&RoadLine>().iter(&app.world).count(), line_count);
+    assert_eq!(player_translation(&mut app), player_start);
+    let game_speed = app.world.resource::<GameSpeed>();
+    assert_eq!(game_speed.time_elapsed, 0.0);
+    assert_eq!(game_speed.multiplier, 1.0);
+    assert_eq!(app.world.resource::<EnemySpawnTimer>().0.elapsed_secs(), 0.0);
+}
+
+#[test]
+fn going_back_to_the_main_menu_closes_the_garage() {
+    let mut app = test_app();
+    app.init_resource::<Input<KeyCode>>()
+        .init_resource::<GarageMenu>()
+        .init_resource::<StatsMenu>()
+        .init_resource::<AchievementsMenu>()
+        .add_event::<UiClick>()
+        .add_systems(Update, (open_main_menu, navigate_garage).chain().in_set(GameSet::Ui));
+
+    app.world.resource_mut::<GameOver>().0 = true;
+    app.world.resource_mut::<GarageMenu>().open = true;
+    app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Escape);
+    app.update();
+
+    assert!(app.world.resource::<InMenu>().0);
+    assert!(!app.world.resource::<GarageMenu>().open);
+
+    // Even if it were left open, the garage ignores keys meant for the menu
+    app.world.resource_mut::<GarageMenu>().open = true;
+    let mut input = app.world.resource_mut::<Input<KeyCode>>();
+    input.clear();
+    input.press(KeyCode::Down);
+    app.update();
+
+    assert_eq!(app.world.resource::<GarageMenu>().selected, 0);
+}
+
+#[test]
+fn shipped_stages_are_valid() {
+    let stages = Stages::parse(include_str!("../assets/stages.ron")).unwrap();
+    assert!(stages.0.len() > 1);
+
+    // A broken file is reported instead of panicking
+    assert!(Stages::parse("[]").is_err());
+    assert!(Stages::parse("not ron").is_err());
+}
+
+#[test]
+fn reaching_a_checkpoint_changes_stage_and_lanes() {
+    let mut app = test_app();
+    let highway = app.world.resource::<Stages>().get(1).clone();
+    app.world.resource_mut::<GameSpeed>().distance = highway.distance * UNITS_PER_METER;
+
+    app.update();
+
+    assert_eq!(app.world.resource::<CurrentStage>().0, 1);
+    let distance = app.world.resource::<GameSpeed>().distance;
+    let lines: Vec<Vec3> = app
+        .world
+        .query_filtered::<&Transform, With<RoadLine>>()
+        .iter(&app.world)
+        .map(|transform| transform.translation)
+        .collect();
+    let layout = app.world.resource::<RoadLayout>();
+    for line in lines {
+        let shape = layout.shape_at(distance + line.y, &highway);
+        let boundary = (line.x - shape.left_edge()) / shape.lane_width;
+        assert!((boundary - boundary.round()).abs() < 0.01);
+    }
+}
+
+#[test]
+fn player_is_clamped_to_the_road() {
+    let mut app = test_app();
+    app.world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(&mut app.world)
+        .translation
+        .x = 1000.0;
+
+    app.update();
+
+    let player_pos = player_translation(&mut app);
+    let distance = app.world.resource::<GameSpeed>().distance;
+    let stage = app.world.resource::<Stages>().get(0).clone();
+    let (_, right) = app.world.resource::<RoadLayout>().shape_at(distance + player_pos.y, &stage).bounds();
+    assert_eq!(player_pos.x, right);
+}
+
+// How far the player moves sideways in one frame of full steering
+fn steer_for_one_frame(weather: Weather) -> f32 {
+    let mut app = test_app();
+    *app.world.resource_mut::<Atmosphere>() = Atmosphere {
+        daylight: 1.0,
+        weather,
+        intensity: 1.0,
+    };
+    app.world.resource_mut::<PlayerInput>().steer = 1.0;
+    let start_x = player_translation(&mut app).x;
+
+    app.update();
+
+    player_translation(&mut app).x - start_x
+}
+
+#[test]
+fn snow_reduces_grip() {
+    let clear = steer_for_one_frame(Weather::Clear);
+    let snow = steer_for_one_frame(Weather::Snow);
+    assert!(snow > 0.0);
+    assert!(snow < clear);
+}
+
+#[test]
+fn speed_multiplier_caps_at_4x() {
+    let mut app = test_app();
+    app.world.resource_mut::<GameSpeed>().time_elapsed = 1000.0;
+
+    app.update();
+
+    assert_eq!(app.world.resource::<GameSpeed>().multiplier, 4.0);
+}
+
+#[test]
+fn nitro_does_not_push_past_4x() {
+    let mut app = test_app();
+    app.world.resource_mut::<GameSpeed>().time_elapsed = 1000.0;
+    app.world.resource_mut::<ActiveEffects>().activate(PowerUp::Nitro);
+
+    app.update();
+
+    assert_eq!(app.world.resource::<GameSpeed>().multiplier, 4.0);
+}
+
+#[test]
+fn traffic_plugin_runs_on_its_own() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, CorePlugin::default(), TrafficPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(0.25)));
+
+    // Zero-delta first frame, then just over one spawn interval
+    for _ in 0..6 {
+        app.update();
+    }
+
+    let enemies = app.world.query_filtered::<Entity, With<Enemy>>().iter(&app.world).count();
+    assert_eq!(enemies, 1);
+}
+
+#[test]
+fn simulated_runs_with_the_same_seed_match() {
+    let first = crate::simulation::simulate_run(Difficulty::Hard, 7);
+    let second = crate::simulation::simulate_run(Difficulty::Hard, 7);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn simulation_rejects_bad_arguments() {
+    let parse = |line: &str| {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        crate::simulation::SimulationConfig::from_args(&args)
+    };
+
+    assert!(parse("game").is_none());
+    let config = parse("game --simulate --difficulty hard").unwrap().unwrap();
+    assert_eq!(config.runs, 1000);
+    assert_eq!(config.difficulties, vec![Difficulty::Hard]);
+    assert!(parse("game --simulate lots").unwrap().is_err());
+    assert!(parse("game --simulate 0").unwrap().is_err());
+    assert!(parse("game --simulate 10 --difficulty insane").unwrap().is_err());
+    assert!(parse("game --simulate 10 --seed abc").unwrap().is_err());
+}
+
+#[test]
+fn fps_cap_holds_each_frame_to_its_share_of_a_second() {
+    let mut settings = Settings::default();
+    settings.video.fps_cap = 30;
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(settings)
+        .add_systems(Last, crate::options::limit_frame_rate);
+    app.update();
+
+    let start = std::time::Instant::now();
+    for _ in 0..3 {
+        app.update();
+    }
+
+    // The first frame was already under way when the clock started
+    assert!(start.elapsed() >= Duration::from_secs_f32(2.0 / 30.0));
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use crate::components::SplitNotice;
+use crate::game::GameSet;
+use crate::persistence;
+use crate::resources::{GameOver, Settings, TrialRecord, TRIAL_RECORD_FILE};
+use crate::systems::{CheckpointReached, TimeTrial, CHECKPOINTS, CHECKPOINT_BONUS, COURSE_LENGTH};
+
+const SPLIT_NOTICE_SECONDS: f32 = 2.0;
+
+// Loads and saves the best time trial, and shows each split against it
+pub struct TimeTrialBoardPlugin;
+
+impl Plugin for TimeTrialBoardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (load_trial_record, spawn_split_notice))
+            .add_systems(Update, (save_trial_record, show_split_notice).in_set(GameSet::Ui));
+    }
+}
+
+pub fn load_trial_record(mut commands: Commands) {
+    if let Some(record) = persistence::load::<TrialRecord>(TRIAL_RECORD_FILE) {
+        commands.insert_resource(record);
+    }
+}
+
+pub fn save_trial_record(game_over: Res<GameOver>, trial: Res<TimeTrial>, record: Res<TrialRecord>) {
+    if game_over.is_changed() && game_over.0 && trial.new_record {
+        persistence::save(TRIAL_RECORD_FILE, &*record);
+    }
+}
+
+pub fn spawn_split_notice(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 36.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(28.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            z_index: ZIndex::Global(5),
+            ..default()
+        },
+        SplitNotice {
+            timer: Timer::from_seconds(SPLIT_NOTICE_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+// "Checkpoint 2  21.40s (-0.85)", highlighted when ahead of the best and in the
+// danger color when behind
+pub fn show_split_notice(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut checkpoint_events: EventReader<CheckpointReached>,
+    mut notice_query: Query<(&mut SplitNotice, &mut Text, &mut Visibility)>,
+) {
+    let Ok((mut notice, mut text, mut visibility)) = notice_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(event) = checkpoint_events.iter().last() {
+        let palette = settings.gameplay.palette();
+        let label = if event.is_finish() {
+            "Finish".to_string()
+        } else {
+            format!("Checkpoint {}", event.index + 1)
+        };
+        let mut value = format!("{}  {:.2}s", label, event.split);
+        text.sections[0].style.color = Color::WHITE;
+        if let Some(delta) = event.delta {
+            value.push_str(&format!(" ({:+.2})", delta));
+            text.sections[0].style.color = if delta <= 0.0 { palette.highlight } else { palette.danger };
+        }
+        if !event.is_finish() {
+            value.push_str(&format!("\n+{:.0}s", CHECKPOINT_BONUS));
+        }
+        text.sections[0].value = value;
+        *visibility = Visibility::Visible;
+        notice.timer.reset();
+    }
+
+    if notice.timer.tick(time.delta()).just_finished() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+// Shown on the main menu next to the mode
+pub fn best_time_label(record: &TrialRecord) -> String {
+    match record.best_time() {
+        Some(time) => format!("Best {:.2}s", time),
+        None => "No time set".to_string(),
+    }
+}
+
+// Course length and checkpoints before the finish, for the menu
+pub fn course_label() -> String {
+    format!("{:.1} km, {} checkpoints", COURSE_LENGTH / 1000.0, CHECKPOINTS - 1)
+}
@@ -1,6 +1,45 @@
 use bevy::prelude::*;
-use crate::components::{GameOverStats, GameOverUI};
-use crate::resources::{GameOver, GameSpeed};
+use crate::components::{
+    Armor, Explosion, GameOverStats, GameOverTitle, GameOverUI, Hazard, HudText, PauseUI, PowerUp, StageBanner,
+};
+use crate::game::{GameSet, RestartRun, RunSetup};
+use crate::playfield::UNITS_PER_METER;
+use crate::garage::bank_run_coins;
+use crate::missions::mission_list;
+use crate::resources::{
+    ActiveEffects, GameMode, GameOver, GameSpeed, Heat, HighScore, InMenu, OncomingTime, Paused, Profile, RunCoins, Score,
+    Settings, StageDef, Stages, TrialRecord, MAX_WANTED_LEVEL,
+};
+use crate::systems::{save_personal_best, StageChanged, TimeTrial, CHECKPOINTS};
+
+const STAGE_BANNER_SECONDS: f32 = 2.5;
+
+// A menu button or key was used
+#[derive(Event)]
+pub struct UiClick;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UiClick>()
+            .add_systems(Startup, (spawn_game_over_ui, spawn_pause_ui, spawn_stage_banner, spawn_hud))
+            .add_systems(RunSetup, (hide_game_over_screen, show_first_stage_banner))
+            .add_systems(Update, toggle_pause.in_set(GameSet::Input))
+            .add_systems(
+                Update,
+                (
+                    display_game_over_screen.after(save_personal_best).after(bank_run_coins),
+                    display_pause_screen,
+                    restart_game,
+                    apply_palette,
+                    display_stage_banner,
+                    update_hud,
+                )
+                    .in_set(GameSet::Ui),
+            );
+    }
+}
 
 pub fn spawn_game_over_ui(mut commands: Commands,) {
     commands
@@ -25,27 +64,30 @@ pub fn spawn_game_over_ui(mut commands: Commands,) {
         ))
         .with_children(|parent| {
             // Title
-            parent.spawn(TextBundle {
-                text: Text::from_section(
-                    "Game Over",
-                    TextStyle {
-                        font_size: 60.0,
-                        color: Color::RED,
+            parent.spawn((
+                TextBundle {
+                    text: Text::from_section(
+                        "Game Over",
+                        TextStyle {
+                            font_size: 60.0,
+                            color: Color::RED,
+                            ..default()
+                        },
+                    ),
+                    style: Style {
+                        margin: UiRect::all(Val::Px(20.0)),
                         ..default()
                     },
-                ),
-                style: Style {
-                    margin: UiRect::all(Val::Px(20.0)),
                     ..default()
                 },
-                ..default()
-            });
+                GameOverTitle,
+            ));
 
             // Stats
             parent.spawn((
                 TextBundle {
                     text: Text::from_section(
-                        "Survival Time: 0.0s\nFinal Speed Multiplier: 0.0x\nDistance Traveled: 0m",
+                        "Score: 0\nSurvival Time: 0.0s\nBest Time: 0.0s\nFinal Speed Multiplier: 0.0x\nDistance Traveled: 0m\nCoins Earned: 0 (Wallet: 0)",
                         TextStyle {
                             font_size: 30.0,
                             color: Color::WHITE,
@@ -61,10 +103,70 @@ pub fn spawn_game_over_ui(mut commands: Commands,) {
                 GameOverStats,
             ));
 
+            parent.spawn(mission_list());
+
             // Instructions
             parent.spawn(TextBundle {
                 text: Text::from_section(
-                    "Press [R] to Restart or [Esc] to Quit",
+                    "Press [R] to Restart, [G] for the Garage, [H] for Achievements, [T] for Stats or [Esc] for the Menu",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                ..default()
+            });
+        });
+}
+
+pub fn spawn_pause_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Px(0.0),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.6)),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            PauseUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Paused",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                style: Style {
+                    margin: UiRect::all(Val::Px(20.0)),
+                    ..default()
+                },
+                ..default()
+            });
+
+            parent.spawn(mission_list());
+
+            parent.spawn(TextBundle {
+                text: Text::from_section(
+                    "Press [P] to Resume, [O] for Options or [M] to Mute",
                     TextStyle {
                         font_size: 20.0,
                         color: Color::GRAY,
@@ -80,11 +182,220 @@ pub fn spawn_game_over_ui(mut commands: Commands,) {
         });
 }
 
+pub fn spawn_stage_banner(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 48.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(20.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        StageBanner {
+            timer: Timer::from_seconds(STAGE_BANNER_SECONDS, TimerMode::Once),
+        },
+    ));
+}
+
+pub fn spawn_hud(mut commands: Commands) {
+    commands.spawn((
+        TextBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        },
+        HudText,
+    ));
+}
+
+// Score, coins, time against the traffic, armor left and wanted level, then one
+// line per active power-up with the seconds it has left. Time trials add the
+// clock and the way to the next checkpoint on top. Warnings and power-ups take
+// their color from the palette.
+#[allow(clippy::too_many_arguments)]
+pub fn update_hud(
+    mode: Res<GameMode>,
+    trial: Res<TimeTrial>,
+    game_speed: Res<GameSpeed>,
+    score: Res<Score>,
+    run_coins: Res<RunCoins>,
+    oncoming_time: Res<OncomingTime>,
+    heat: Res<Heat>,
+    effects: Res<ActiveEffects>,
+    settings: Res<Settings>,
+    armor_query: Query<&Armor>,
+    mut hud_query: Query<&mut Text, With<HudText>>,
+) {
+    let Ok(mut text) = hud_query.get_single_mut() else {
+        return;
+    };
+
+    let palette = settings.gameplay.palette();
+    let mut lines = Vec::new();
+    if *mode == GameMode::TimeTrial {
+        lines.push((format!("Time: {:.1}s", trial.time_left.max(0.0)), Color::WHITE));
+        let next = trial.next_checkpoint();
+        if next < CHECKPOINTS {
+            let meters = (TimeTrial::checkpoint_distance(next) - game_speed.distance) / UNITS_PER_METER;
+            let label = if next + 1 == CHECKPOINTS {
+                "Finish".to_string()
+            } else {
+                format!("Checkpoint {}/{}", next + 1, CHECKPOINTS - 1)
+            };
+            lines.push((format!("{}: {}m", label, meters.max(0.0) as i32), Color::WHITE));
+        }
+    }
+    lines.push((format!("Score: {}\nCoins: {}", score.0 as i32, run_coins.0), Color::WHITE));
+    if oncoming_time.0 > 0.0 {
+        lines.push((format!("Oncoming: {:.1}s", oncoming_time.0), palette.danger));
+    }
+    if let Some(armor) = armor_query.get_single().ok().filter(|armor| armor.0 > 0) {
+        lines.push((format!("Armor: {}", armor.0), Color::WHITE));
+    }
+    let wanted = heat.wanted_level();
+    if wanted > 0 {
+        lines.push((
+            format!("Wanted: {}{}", "*".repeat(wanted), "-".repeat(MAX_WANTED_LEVEL - wanted)),
+            palette.danger,
+        ));
+    }
+    for kind in PowerUp::ALL {
+        if let Some(remaining) = effects.remaining(kind) {
+            lines.push((format!("{} {:.1}s", kind.label(), remaining), palette.highlight));
+        }
+    }
+
+    let line_count = lines.len();
+    text.sections = lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, (line, color))| {
+            let value = if i + 1 < line_count { line + "\n" } else { line };
+            TextSection::new(value, TextStyle { font_size: 24.0, color, ..default() })
+        })
+        .collect();
+}
+
+fn show_stage_banner(
+    banner: &mut StageBanner,
+    text: &mut Text,
+    visibility: &mut Visibility,
+    index: usize,
+    stage: &StageDef,
+) {
+    text.sections[0].value = format!("Stage {}\n{}", index + 1, stage.name);
+    *visibility = Visibility::Visible;
+    banner.timer.reset();
+}
+
+pub fn show_first_stage_banner(
+    stages: Res<Stages>,
+    mut banner_query: Query<(&mut StageBanner, &mut Text, &mut Visibility)>,
+) {
+    if let Ok((mut banner, mut text, mut visibility)) = banner_query.get_single_mut() {
+        show_stage_banner(&mut banner, &mut text, &mut visibility, 0, stages.get(0));
+    }
+}
+
+pub fn display_stage_banner(
+    time: Res<Time>,
+    stages: Res<Stages>,
+    paused: Res<Paused>,
+    in_menu: Res<InMenu>,
+    mut stage_events: EventReader<StageChanged>,
+    mut banner_query: Query<(&mut StageBanner, &mut Text, &mut Visibility)>,
+) {
+    let Ok((mut banner, mut text, mut visibility)) = banner_query.get_single_mut() else {
+        return;
+    };
+
+    if let Some(event) = stage_events.iter().last() {
+        show_stage_banner(&mut banner, &mut text, &mut visibility, event.index, stages.get(event.index));
+    }
+
+    // Holds while paused or behind the menu, but still runs out on the Game Over screen
+    if paused.0 || in_menu.0 {
+        return;
+    }
+    if banner.timer.tick(time.delta()).just_finished() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub fn toggle_pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_over: Res<GameOver>,
+    in_menu: Res<InMenu>,
+    mut paused: ResMut<Paused>,
+    mut click_events: EventWriter<UiClick>,
+) {
+    if keyboard_input.just_pressed(KeyCode::P) && !game_over.0 && !in_menu.0 {
+        paused.0 = !paused.0;
+        click_events.send(UiClick);
+    }
+}
+
+pub fn display_pause_screen(paused: Res<Paused>, mut ui_query: Query<&mut Visibility, With<PauseUI>>) {
+    if paused.is_changed() {
+        if let Ok(mut visibility) = ui_query.get_single_mut() {
+            *visibility = if paused.0 { Visibility::Visible } else { Visibility::Hidden };
+        }
+    }
+}
+
+// Each split with how far ahead or behind the best run it was
+fn trial_splits(trial: &TimeTrial) -> String {
+    trial
+        .splits
+        .iter()
+        .zip(&trial.deltas)
+        .map(|(split, delta)| match delta {
+            Some(delta) => format!("{:.2}s ({:+.2})", split, delta),
+            None => format!("{:.2}s", split),
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn display_game_over_screen(
     game_over: Res<GameOver>,
+    mode: Res<GameMode>,
+    trial: Res<TimeTrial>,
+    record: Res<TrialRecord>,
     game_speed: Res<GameSpeed>,
+    score: Res<Score>,
+    run_coins: Res<RunCoins>,
+    profile: Res<Profile>,
+    high_score: Res<HighScore>,
     mut ui_query: Query<&mut Visibility, With<GameOverUI>>,
-    mut stats_query: Query<&mut Text, With<GameOverStats>>,
+    mut title_query: Query<&mut Text, (With<GameOverTitle>, Without<GameOverStats>)>,
+    mut stats_query: Query<&mut Text, (With<GameOverStats>, Without<GameOverTitle>)>,
 ) {
     if game_over.is_changed() && game_over.0 {
         // Show the game over UI
@@ -92,59 +403,102 @@ pub fn display_game_over_screen(
             *visibility = Visibility::Visible;
         }
 
+        if let Ok(mut text) = title_query.get_single_mut() {
+            text.sections[0].value = match *mode {
+                GameMode::TimeTrial if trial.new_record => "New Best Time!",
+                GameMode::TimeTrial if trial.finished => "Finished!",
+                GameMode::TimeTrial if trial.timed_out() => "Time's Up",
+                _ => "Game Over",
+            }
+            .to_string();
+        }
+
+        // A time trial shows its splits against the best instead of the survival stats
+        if *mode == GameMode::TimeTrial {
+            if let Ok(mut text) = stats_query.get_single_mut() {
+                let coins = run_coins.earned(game_speed.distance / UNITS_PER_METER);
+                let best = record
+                    .best_time()
+                    .map_or("-".to_string(), |time| format!("{:.2}s", time));
+                text.sections[0].value = format!(
+                    "Splits: {}\nBest Time: {}\nScore: {}\nCoins Earned: {} (Wallet: {})",
+                    trial_splits(&trial),
+                    best,
+                    score.0 as i32,
+                    coins,
+                    profile.coins
+                );
+            }
+            return;
+        }
+
         // Update stats text
         if let Ok(mut text) = stats_query.get_single_mut() {
             let survival_time = game_speed.time_elapsed;
             let final_speed = game_speed.multiplier;
-            let distance = (survival_time * 50.0) as i32;
-            
+            let meters = game_speed.distance / UNITS_PER_METER;
+            let coins = run_coins.earned(meters);
+
             text.sections[0].value = format!(
-                "Survival Time: {:.1}s\nFinal Speed Multiplier: {:.1}x\nDistance Traveled: {}m",
-                survival_time, final_speed, distance
+                "Score: {}\nSurvival Time: {:.1}s\nBest Time: {:.1}s\nFinal Speed Multiplier: {:.1}x\nDistance Traveled: {}m\nCoins Earned: {} (Wallet: {})",
+                score.0 as i32, survival_time, high_score.best_time, final_speed, meters as i32, coins, profile.coins
             );
         }
     }
 }
 
+pub fn hide_game_over_screen(mut ui_query: Query<&mut Visibility, With<GameOverUI>>) {
+    if let Ok(mut visibility) = ui_query.get_single_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
 pub fn restart_game(
     keyboard_input: Res<Input<KeyCode>>,
-    mut game_over: ResMut<GameOver>,
-    mut game_speed: ResMut<GameSpeed>,
-    mut ui_query: Query<&mut Visibility, With<GameOverUI>>,
-    enemies: Query<Entity, With<crate::components::Enemy>>,
-    mut commands: Commands,
-    mut player_query: Query<(&mut Transform, &mut crate::components::Velocity), With<crate::components::Player>>,
-    mut enemy_timer: ResMut<crate::resources::EnemySpawnTimer>,
+    game_over: Res<GameOver>,
+    in_menu: Res<InMenu>,
+    mut restart_events: EventWriter<RestartRun>,
+    mut click_events: EventWriter<UiClick>,
 ) {
-    if game_over.0 {
-        if keyboard_input.just_pressed(KeyCode::R) {
-            // Reset game state
-            game_over.0 = false;
-            game_speed.time_elapsed = 0.0;
-            game_speed.multiplier = 1.0;
-
-            // Hide game over UI
-            if let Ok(mut visibility) = ui_query.get_single_mut() {
-                *visibility = Visibility::Hidden;
-            }
+    if game_over.0 && !in_menu.0 && keyboard_input.just_pressed(KeyCode::R) {
+        restart_events.send(RestartRun);
+        click_events.send(UiClick);
+        println!("Game restarted!");
+    }
+}
 
-            // Reset player position and velocity
-            if let Ok((mut transform, mut velocity)) = player_query.get_single_mut() {
-                transform.translation = Vec3::new(0.0, -200.0, 10.0);
-                velocity.speed = 150.0;
-            }
+// Recolors meaningful UI and effects when the colorblind palette is toggled
+#[allow(clippy::type_complexity)]
+pub fn apply_palette(
+    settings: Res<Settings>,
+    mut title_query: Query<&mut Text, With<GameOverTitle>>,
+    mut explosion_query: Query<(Ref<Explosion>, &mut Sprite)>,
+    mut hazard_query: Query<(Ref<Hazard>, &mut Sprite), Without<Explosion>>,
+    mut power_up_query: Query<(Ref<PowerUp>, &mut Sprite), (Without<Explosion>, Without<Hazard>)>,
+) {
+    let palette = settings.gameplay.palette();
 
-            // Remove all enemies
-            for entity in enemies.iter() {
-                commands.entity(entity).despawn();
-            }
+    if settings.is_changed() {
+        for mut text in title_query.iter_mut() {
+            text.sections[0].style.color = palette.danger;
+        }
+    }
 
-            // Reset enemy spawn timer
-            enemy_timer.0.reset();
+    for (explosion, mut sprite) in explosion_query.iter_mut() {
+        if explosion.is_added() || settings.is_changed() {
+            sprite.color = palette.explosion;
+        }
+    }
 
-            println!("Game restarted!");
-        } else if keyboard_input.just_pressed(KeyCode::Escape) {
-            std::process::exit(0);
+    for (hazard, mut sprite) in hazard_query.iter_mut() {
+        if hazard.is_added() || settings.is_changed() {
+            sprite.color = palette.hazard;
         }
     }
-}
\ No newline at end of file
+
+    for (power_up, mut sprite) in power_up_query.iter_mut() {
+        if power_up.is_added() || settings.is_changed() {
+            sprite.color = palette.power_up;
+        }
+    }
+}